@@ -0,0 +1,141 @@
+//! Baseline throughput for directory loading and file extraction, to
+//! measure indexing/caching changes (a HashMap lookup index, a block
+//! cache, a smarter FAT iterator) against. Run with `cargo bench`.
+//!
+//! There's no shared synthetic-image builder elsewhere in the crate, so
+//! this benchmark assembles its own minimal NWFS286 image in memory, in
+//! the same byte layout the unit tests in `volume.rs`/`voltab.rs` use.
+
+use std::io::Write;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use nwfs::image::PartitionSelector;
+use nwfs::session::Session;
+
+const BLOCK_SIZE: u32 = 4096;
+const NUM_SMALL_FILES: u32 = 200;
+const BIG_FILE_BLOCKS: u32 = 256;
+const FAT_FIRST_BLOCK: u32 = 1;
+const FAT_NUM_BLOCKS: u32 = 1;
+const DIR_FIRST_BLOCK: u32 = FAT_FIRST_BLOCK + FAT_NUM_BLOCKS;
+const DIR_NUM_BLOCKS: u32 = 16;
+const DATA_FIRST_BLOCK: u32 = DIR_FIRST_BLOCK + DIR_NUM_BLOCKS;
+const TOTAL_BLOCKS: u32 = DATA_FIRST_BLOCK + NUM_SMALL_FILES + BIG_FILE_BLOCKS;
+const START_SECTOR: u32 = 2; // skip the hotfix table (sector 0) and volume table (sector 1)
+const FAT_END: u32 = 0xffff_ffff;
+
+/// Build a synthetic NWFS286 image with `NUM_SMALL_FILES` one-block files
+/// and one `BIG_FILE_BLOCKS`-block file in the root directory, and write it
+/// to a fresh temp file. Returns the path; the caller is responsible for
+/// removing it.
+fn build_synthetic_image() -> std::path::PathBuf {
+    let sectors_per_block = BLOCK_SIZE / 512;
+    let partition_offset: u64 = 512; // MBR is one sector
+    let segment_offset = partition_offset + u64::from(START_SECTOR) * 512;
+    let partition_len = u64::from(START_SECTOR) * 512 + u64::from(TOTAL_BLOCKS) * u64::from(BLOCK_SIZE);
+    let image_len = partition_offset + partition_len;
+
+    let mut image = vec![0u8; image_len as usize];
+
+    // MBR: one NWFS286 (type 0x64) partition starting at LBA 1.
+    image[0x1be + 4] = 0x64;
+    image[0x1be + 8..0x1be + 12].copy_from_slice(&1u32.to_le_bytes());
+    image[0x1be + 12..0x1be + 16].copy_from_slice(&((partition_len / 512) as u32).to_le_bytes());
+    image[0x1fe..0x200].copy_from_slice(&[0x55, 0xaa]);
+
+    // Hotfix table: zero entries.
+    let hotfix_off = partition_offset as usize;
+    image[hotfix_off..hotfix_off + 4].copy_from_slice(&0u32.to_le_bytes());
+
+    // Volume table: one volume, one segment, covering the whole image.
+    let voltab_off = partition_offset as usize + 512;
+    image[voltab_off..voltab_off + 4].copy_from_slice(&1u32.to_le_bytes());
+    let entry_off = voltab_off + 4;
+    let name = b"BENCH";
+    image[entry_off] = name.len() as u8;
+    image[entry_off + 1..entry_off + 1 + name.len()].copy_from_slice(name);
+    let mut off = entry_off + 1 + 15;
+    image[off..off + 4].copy_from_slice(&0u32.to_le_bytes()); // volume_number
+    off += 4;
+    image[off..off + 4].copy_from_slice(&0u32.to_le_bytes()); // segment_num
+    off += 4;
+    image[off..off + 4].copy_from_slice(&1u32.to_le_bytes()); // num_segments_total
+    off += 4;
+    image[off..off + 4].copy_from_slice(&START_SECTOR.to_le_bytes()); // start_sector
+    off += 4;
+    image[off..off + 4].copy_from_slice(&(TOTAL_BLOCKS * sectors_per_block).to_le_bytes()); // num_sectors
+    off += 4;
+    image[off..off + 4].copy_from_slice(&BLOCK_SIZE.to_le_bytes()); // block_size
+
+    let block_offset = |block: u32| segment_offset as usize + block as usize * BLOCK_SIZE as usize;
+
+    // FAT: every small file is a single block (FAT_END); the big file is
+    // one contiguous chain.
+    let fat_off = block_offset(FAT_FIRST_BLOCK);
+    for i in 0..NUM_SMALL_FILES {
+        let block = DATA_FIRST_BLOCK + i;
+        let entry_off = fat_off + block as usize * 4;
+        image[entry_off..entry_off + 4].copy_from_slice(&FAT_END.to_le_bytes());
+    }
+    let big_file_first_block = DATA_FIRST_BLOCK + NUM_SMALL_FILES;
+    for i in 0..BIG_FILE_BLOCKS {
+        let block = big_file_first_block + i;
+        let next = if i + 1 == BIG_FILE_BLOCKS { FAT_END } else { block + 1 };
+        let entry_off = fat_off + block as usize * 4;
+        image[entry_off..entry_off + 4].copy_from_slice(&next.to_le_bytes());
+    }
+
+    // Root directory: one 128-byte entry per file.
+    let dir_off = block_offset(DIR_FIRST_BLOCK);
+    let write_entry = |image: &mut [u8], slot: usize, first_block: u32, length: u32, name: &str| {
+        let off = dir_off + slot * 128;
+        image[off..off + 4].copy_from_slice(&first_block.to_le_bytes());
+        image[off + 4..off + 8].copy_from_slice(&0u32.to_le_bytes()); // parent_id = root
+        image[off + 8..off + 12].copy_from_slice(&length.to_le_bytes());
+        let name_bytes = name.as_bytes();
+        image[off + 0x0f] = name_bytes.len() as u8;
+        image[off + 0x10..off + 0x10 + name_bytes.len()].copy_from_slice(name_bytes);
+    };
+    for i in 0..NUM_SMALL_FILES {
+        write_entry(&mut image, i as usize, DATA_FIRST_BLOCK + i, BLOCK_SIZE, &format!("FILE{i:04}.DAT"));
+    }
+    write_entry(
+        &mut image,
+        NUM_SMALL_FILES as usize,
+        big_file_first_block,
+        BIG_FILE_BLOCKS * BLOCK_SIZE,
+        "BIG.DAT",
+    );
+
+    let path = std::env::temp_dir().join(format!("nwfs_bench_{}.img", std::process::id()));
+    let mut f = std::fs::File::create(&path).unwrap();
+    f.write_all(&image).unwrap();
+    path
+}
+
+fn bench_directory_load(c: &mut Criterion) {
+    let path = build_synthetic_image();
+    let path_str = path.to_string_lossy().into_owned();
+
+    c.bench_function("load root directory (200 files)", |b| {
+        b.iter(|| {
+            let session = Session::open(&path_str, PartitionSelector::Auto).unwrap();
+            std::hint::black_box(session.dir_entries.len());
+        });
+    });
+
+    c.bench_function("extract largest file (1 MiB)", |b| {
+        b.iter(|| {
+            let mut session = Session::open(&path_str, PartitionSelector::Auto).unwrap();
+            let item = session.find_file("BIG.DAT").unwrap();
+            let data = session.read_file(&item).unwrap();
+            std::hint::black_box(data.len());
+        });
+    });
+
+    std::fs::remove_file(&path).ok();
+}
+
+criterion_group!(benches, bench_directory_load);
+criterion_main!(benches);
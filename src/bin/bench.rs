@@ -0,0 +1,95 @@
+//! Benchmark directory-read and extraction throughput for a given image.
+//! Not part of the user-facing `nwfs` CLI -- run directly when profiling
+//! FAT-cache or read-ahead changes to get a before/after number.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+
+use clap::Parser;
+
+use nwfs::dirent::DirEntry;
+use nwfs::image::PartitionSelector;
+use nwfs::session::{Format, Session};
+use nwfs::voltab::INITIAL_DIR_BLOCKS;
+use nwfs::{nwfs286, nwfs386};
+
+#[derive(Parser)]
+#[command(name = "nwfs-bench", about = "Measure directory-read and extraction throughput")]
+struct Args {
+    image: PathBuf,
+    #[arg(long)]
+    partition: Option<usize>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if let Err(err) = run(&args) {
+        eprintln!("error: {err}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn run(args: &Args) -> nwfs::Result<()> {
+    let image_str = args.image.to_string_lossy().into_owned();
+    let selector = match args.partition {
+        Some(index) => PartitionSelector::Index(index),
+        None => PartitionSelector::Auto,
+    };
+
+    let mut session = Session::open(&image_str, selector)?;
+
+    let dir_start = Instant::now();
+    let entries = match session.format {
+        Format::Nwfs286 => nwfs286::read_directory_entries(
+            &session.vol,
+            &mut session.file,
+            session.vol.dir_first_block(),
+            INITIAL_DIR_BLOCKS,
+        )?,
+        Format::Nwfs386 => nwfs386::read_directory_entries(
+            &session.vol,
+            &mut session.file,
+            session.vol.dir_first_block(),
+            INITIAL_DIR_BLOCKS,
+        )?,
+    };
+    let dir_elapsed = dir_start.elapsed();
+    let dir_blocks_per_sec = INITIAL_DIR_BLOCKS as f64 / dir_elapsed.as_secs_f64().max(f64::EPSILON);
+    println!(
+        "directory read: {} entries, {} blocks in {:.3}ms ({:.1} blocks/sec)",
+        entries.len(),
+        INITIAL_DIR_BLOCKS,
+        dir_elapsed.as_secs_f64() * 1000.0,
+        dir_blocks_per_sec
+    );
+
+    let largest = entries
+        .iter()
+        .filter_map(|e| match e {
+            DirEntry::File(f) => Some(f.clone()),
+            DirEntry::Directory(_) => None,
+        })
+        .max_by_key(|f| f.length);
+
+    match largest {
+        Some(item) => {
+            let extract_start = Instant::now();
+            let data = session.read_file(&item)?;
+            let extract_elapsed = extract_start.elapsed();
+            let mb = data.len() as f64 / (1024.0 * 1024.0);
+            let mb_per_sec = mb / extract_elapsed.as_secs_f64().max(f64::EPSILON);
+            println!(
+                "extraction: '{}' ({} bytes) in {:.3}ms ({:.2} MB/sec)",
+                item.name,
+                data.len(),
+                extract_elapsed.as_secs_f64() * 1000.0,
+                mb_per_sec
+            );
+        }
+        None => println!("extraction: no files in root directory to benchmark"),
+    }
+
+    Ok(())
+}
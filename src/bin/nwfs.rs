@@ -0,0 +1,2037 @@
+//! Unified `nwfs` CLI: inspect, browse, and extract NWFS286/NWFS386
+//! volumes. Replaces the old standalone `inspect`/`shell`/`transfer`
+//! binaries with one tool and a shared [`nwfs::session::Session`].
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
+
+use nwfs::dirent::{format_entry, DeletedFilter, DirEntry, EntryKind};
+use nwfs::dosdate::TimestampFormat;
+use nwfs::image::PartitionSelector;
+use nwfs::session::{BlockState, Session};
+
+#[derive(Parser)]
+#[command(name = "nwfs", about = "Inspect and extract NetWare NWFS286/NWFS386 volumes")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Tolerate a block_size disagreement between a volume's segments
+    /// instead of refusing to open it.
+    #[arg(long, global = true)]
+    lenient: bool,
+    /// How to render timestamps in directory listings: dos, iso8601, or
+    /// rfc3339.
+    #[arg(long, global = true, default_value = "dos")]
+    timestamp_format: TimestampFormat,
+    /// Select a volume by its numeric `volume_number` instead of taking
+    /// the first one found in the partition's volume segment table --
+    /// useful when a volume's name is corrupt or duplicated.
+    #[arg(long, global = true)]
+    select_volume_by_id: Option<u32>,
+    /// Force the block size (in KB: 4, 8, 16, 32, or 64) used for every
+    /// block-address calculation, instead of the value parsed from the
+    /// volume header -- for recovery when that header field is corrupt
+    /// but the volume's data and segment layout are otherwise intact.
+    /// A wrong value yields garbage reads, not an error.
+    #[arg(long, global = true, value_parser = parse_block_size_kb)]
+    block_size: Option<u32>,
+    /// Cache the parsed FAT and directory table in a sidecar
+    /// `<image>.nwfs-cache` file, and reuse it on a later run against the
+    /// same image (same size/mtime) with the same dir-copy, volume
+    /// selector, and block-size override -- for repeatedly reopening a
+    /// large volume without re-walking its whole FAT and directory table
+    /// from disk every time.
+    #[arg(long, global = true)]
+    cache: bool,
+    /// Last-resort manual override for the order segments are assembled
+    /// in, as a comma-separated list of indices into the matched segment
+    /// entries in on-disk table order (e.g. `0,2,1`) -- for a volume whose
+    /// segment metadata (`segment_num`/`num_segments_total`) is itself too
+    /// corrupt for automatic assembly to trust. A wrong order silently
+    /// produces a volume with scrambled or missing data, not an error; use
+    /// it only once the correct order is known by other means.
+    #[arg(long, global = true, value_delimiter = ',')]
+    segments: Option<Vec<u32>>,
+}
+
+fn parse_block_size_kb(s: &str) -> std::result::Result<u32, String> {
+    let kb: u32 = s.parse().map_err(|_| format!("'{s}' is not a number"))?;
+    match kb {
+        4 | 8 | 16 | 32 | 64 => Ok(kb * 1024),
+        other => Err(format!("block size must be one of 4, 8, 16, 32, 64 (KB), got {other}")),
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Dump volume, segment, and directory information.
+    Inspect {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Only print the volume summary line, not the full directory dump.
+        #[arg(short, long)]
+        quiet: bool,
+        /// Read directory copy 1 (primary) or 2 (mirror), for recovery
+        /// when the primary directory table is damaged, or for comparing
+        /// the two copies by running `inspect` once per copy. Works for
+        /// both NWFS286 and NWFS386 images.
+        #[arg(long, default_value_t = 1)]
+        dir_copy: u8,
+        /// Write the whole FAT to this path as CSV (entry_index,next_block),
+        /// for offline analysis of allocation and chains in a spreadsheet.
+        #[arg(long)]
+        dump_fat: Option<PathBuf>,
+        /// Also copy just the NetWare partition out to this path, as if by
+        /// `dump-partition` -- a shortcut for inspecting and carving out a
+        /// partition in one command instead of two.
+        #[arg(long)]
+        extract_partition: Option<PathBuf>,
+    },
+    /// Start an interactive read-only shell.
+    Shell {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Read directory copy 1 (primary) or 2 (mirror), for recovery
+        /// when the primary directory table is damaged.
+        #[arg(long, default_value_t = 1)]
+        dir_copy: u8,
+        /// Run commands from this file instead of an interactive prompt:
+        /// one command per line, with no prompt printed and a summary of
+        /// failures at the end instead of stopping on the first error.
+        #[arg(long)]
+        script: Option<PathBuf>,
+        /// Start navigation in this directory id instead of the volume's
+        /// root, resolved the same way the shell's own `cd #<id>` command
+        /// resolves one -- by searching every directory reachable from the
+        /// root, live or deleted. For browsing a surviving subtree when the
+        /// root's own entries are damaged. `dir_id` is assigned positionally
+        /// within each directory's own listing rather than being unique
+        /// across the volume, so `--root 0` errors instead of guessing if
+        /// some subdirectory also happens to sit in slot 0 of its parent;
+        /// navigate there by path instead.
+        #[arg(long)]
+        root: Option<u32>,
+    },
+    /// Extract every file in a directory (non-recursive) to `dest`.
+    Extract {
+        image: PathBuf,
+        dest: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Export the directory table starting at this block instead of
+        /// the volume's root directory.
+        #[arg(long)]
+        at_block: Option<u32>,
+        /// Map NetWare attributes onto host file permissions: a read-only
+        /// file comes out with its owner-write bit cleared. Unix only.
+        #[arg(long)]
+        preserve_attrs: bool,
+        /// Use the original mixed-case filename instead of the 8.3 name,
+        /// for entries that have one recorded. No-op until this crate
+        /// parses long-name namespace entries: every file currently falls
+        /// back to its 8.3 name, same as without this flag.
+        #[arg(long)]
+        preserve_case: bool,
+        /// Read each file's last block in full instead of truncating it to
+        /// the declared length, and report how much slack space (leftover
+        /// bytes from whatever previously occupied that block) came along
+        /// with it. For forensic recovery only; every extracted file grows
+        /// by up to one block's worth of bytes when this is set.
+        #[arg(long)]
+        include_slack: bool,
+        /// Prefix each extracted file's host path with the volume name
+        /// (e.g. `SYS/SYSTEM/LOGIN.EXE`), so extracting from several
+        /// volumes into the same `dest` can't collide on identically named
+        /// directories. Off by default to preserve existing output layout.
+        #[arg(long)]
+        with_volume: bool,
+    },
+    /// Recursively extract every file reachable from the root, preserving
+    /// the directory structure -- unlike `extract`, a single file hitting
+    /// a bad block doesn't abort the rest of the tree.
+    ExtractTree {
+        image: PathBuf,
+        dest: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Map NetWare attributes onto host file permissions: a read-only
+        /// file comes out with its owner-write bit cleared. Unix only.
+        #[arg(long)]
+        preserve_attrs: bool,
+        /// Use the original mixed-case filename instead of the 8.3 name,
+        /// for entries that have one recorded. No-op until this crate
+        /// parses long-name namespace entries: every file currently falls
+        /// back to its 8.3 name, same as without this flag.
+        #[arg(long)]
+        preserve_case: bool,
+        /// Skip files already present at `dest` with the right size, and
+        /// continue a previous run instead of re-extracting everything --
+        /// for retrying just the files a prior run couldn't read because
+        /// of a bad block.
+        #[arg(long)]
+        resume: bool,
+    },
+    /// List the root directory.
+    Ls {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Show create time, modify time, and owner alongside each entry.
+        #[arg(short = 'l', long = "created")]
+        long: bool,
+        /// Read directory copy 1 (primary) or 2 (mirror), for recovery
+        /// when the primary directory table is damaged.
+        #[arg(long, default_value_t = 1)]
+        dir_copy: u8,
+    },
+    /// Print a single file's contents to stdout.
+    Cat {
+        image: PathBuf,
+        name: String,
+        #[arg(long)]
+        partition: Option<usize>,
+    },
+    /// Report how many of each file's blocks fall in hotfix-redirected
+    /// regions, without reading the redirected data.
+    Verify {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+    },
+    /// Copy just the NetWare partition out of a full disk image into a
+    /// standalone raw partition file, for mounting with a kernel driver
+    /// or other tooling that expects the partition at offset 0.
+    DumpPartition {
+        image: PathBuf,
+        out: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+    },
+    /// Print a deterministic SHA-256 fingerprint of the volume's logical
+    /// contents (every file's path and data), for verifying two captures
+    /// of the same disk agree regardless of differing block layout.
+    Fingerprint {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+    },
+    /// Export a full-path inventory of every directory entry, as CSV or
+    /// JSON.
+    Manifest {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// csv or json.
+        #[arg(long, default_value = "csv")]
+        format: ManifestFormat,
+        /// Which entries to include: all, live (skip deleted), or deleted
+        /// (only deleted) -- a forensic investigator wants deleted entries
+        /// front and center, an archivist wants only live ones.
+        #[arg(long, default_value = "all")]
+        deleted: DeletedFilter,
+    },
+    /// Compare two images' file trees and report added, removed, and
+    /// changed files, for confirming what a "before" and "after" capture
+    /// of the same server actually differ on.
+    Diff {
+        image: PathBuf,
+        other: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        #[arg(long)]
+        other_partition: Option<usize>,
+        /// text or json.
+        #[arg(long, default_value = "text")]
+        format: DiffFormat,
+    },
+    /// Compare two images of the same duplexed volume block-by-block and
+    /// report the first block where they diverge, for confirming whether a
+    /// mirror was actually in sync at imaging time.
+    MirrorVerify {
+        image: PathBuf,
+        other: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        #[arg(long)]
+        other_partition: Option<usize>,
+    },
+    /// Restore a deleted file by clearing its `parent_id` deletion marker
+    /// and re-parenting it under an existing directory. Read-only unless
+    /// `--write` is given; without it, prints what would happen and exits.
+    Undelete {
+        image: PathBuf,
+        name: String,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Directory id to restore the file into -- the original parent
+        /// can't be recovered, since the deletion marker overwrote it.
+        #[arg(long)]
+        into: u32,
+        /// Actually modify the image. Without this, only reports whether
+        /// the file was found and its chain reads back cleanly.
+        #[arg(long)]
+        write: bool,
+    },
+    /// Print each immediate subdirectory's total size, sorted descending,
+    /// plus a grand total -- `du -d1`, for deciding what to extract first
+    /// from a large volume.
+    Du {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Directory id to summarize; defaults to the root. Given
+        /// explicitly as `0`, this is resolved the same ambiguity-checked
+        /// way `cd #0` is (see `shell --root`); left unset, it goes
+        /// straight to the real root without that check.
+        #[arg(long)]
+        dir: Option<u32>,
+        /// Sum each file's full FAT chain in blocks instead of its
+        /// `length` field -- slower, but reflects actual disk usage.
+        #[arg(long)]
+        allocated: bool,
+    },
+    /// List entries whose recorded `parent_id` doesn't match any directory
+    /// found while walking the volume, grouped by that missing parent id
+    /// -- the remnants of a directory recovery can no longer resolve.
+    Orphans {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Extract each group's files into `<dir>/<missing-parent-id>/...`
+        /// instead of just listing them.
+        #[arg(long)]
+        extract: Option<PathBuf>,
+    },
+    /// Recursively tally file count and total bytes per owner id under a
+    /// directory -- `du`, grouped by owner instead of by subdirectory.
+    /// There's no bindery loaded here to resolve an owner id to an account
+    /// name, so owners are printed in hex.
+    Owners {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Directory id to summarize; defaults to the root. Given
+        /// explicitly as `0`, this is resolved the same ambiguity-checked
+        /// way `cd #0` is (see `shell --root`); left unset, it goes
+        /// straight to the real root without that check.
+        #[arg(long)]
+        dir: Option<u32>,
+    },
+    /// Print a compact visual map of block allocation across the volume --
+    /// used, free, and hotfix-redirected (bad) blocks -- derived from
+    /// walking every live file and directory's FAT chain, the classic
+    /// defrag-tool block map. A quick visual health check of fragmentation
+    /// and free space.
+    Map {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Blocks shown per row.
+        #[arg(long, default_value_t = 64)]
+        width: usize,
+    },
+    /// Locate well-known NetWare system log files (volume error logs,
+    /// TTS$LOG.ERR, and similar) anywhere in the volume, for a first look
+    /// at what a server logged before it was imaged.
+    Syslogs {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+        /// Extract any logs found into this directory instead of just
+        /// listing them.
+        #[arg(long)]
+        extract: Option<PathBuf>,
+    },
+    /// List every logical volume recorded in the partition's volume
+    /// segment table, grouped by `volume_number` rather than by name, so
+    /// two volumes sharing a name (or both missing one) still show up as
+    /// two separate entries.
+    Volumes {
+        image: PathBuf,
+        #[arg(long)]
+        partition: Option<usize>,
+    },
+}
+
+/// Output format for [`Command::Diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for DiffFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!("unknown diff format '{other}' (expected text or json)")),
+        }
+    }
+}
+
+/// Output format for [`Command::Manifest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ManifestFormat {
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for ManifestFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(format!("unknown manifest format '{other}' (expected csv or json)")),
+        }
+    }
+}
+
+fn selector(partition: Option<usize>) -> PartitionSelector {
+    match partition {
+        Some(index) => PartitionSelector::Index(index),
+        None => PartitionSelector::Auto,
+    }
+}
+
+fn vol_selector(id: Option<u32>) -> nwfs::voltab::VolumeSelector {
+    match id {
+        Some(id) => nwfs::voltab::VolumeSelector::ById(id),
+        None => nwfs::voltab::VolumeSelector::Auto,
+    }
+}
+
+/// Open a session on the primary directory copy, honoring the global
+/// `--select-volume-by-id`, `--block-size`, `--cache`, and `--segments`
+/// flags -- the one place every `cmd_*` function that doesn't need a
+/// non-default `dir_copy` goes through, so those flags don't have to be
+/// threaded into each of them by hand.
+#[allow(clippy::too_many_arguments)]
+fn open_session(
+    image: &str,
+    sel: PartitionSelector,
+    lenient: bool,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<Session> {
+    Session::open_with_volume(image, sel, lenient, 1, vol_selector(vol_id), block_size, cache, segments)
+}
+
+/// Like [`open_session`], but for the handful of commands that support
+/// reading the mirrored directory copy for recovery.
+#[allow(clippy::too_many_arguments)]
+fn open_session_dir_copy(
+    image: &str,
+    sel: PartitionSelector,
+    lenient: bool,
+    dir_copy: u8,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<Session> {
+    Session::open_with_volume(image, sel, lenient, dir_copy, vol_selector(vol_id), block_size, cache, segments)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let lenient = cli.lenient;
+    let ts_format = cli.timestamp_format;
+    let vol_id = cli.select_volume_by_id;
+    let block_size = cli.block_size;
+    let cache = cli.cache;
+    let segments = cli.segments;
+    if let Err(err) = run(cli.command, lenient, ts_format, vol_id, block_size, cache, segments.as_deref()) {
+        eprintln!("error: {err}");
+        return ExitCode::from(exit_code_for(&err));
+    }
+    ExitCode::SUCCESS
+}
+
+/// Map an [`nwfs::NwfsError`] to a distinct process exit code, so a
+/// recovery script can tell "wrong file or partition" apart from "damaged
+/// disk" without scraping the error message text. Codes are grouped by
+/// failure class rather than given one per variant, since a script cares
+/// whether it should retry with a different partition/volume selector or
+/// give up on the image entirely, not which exact check failed:
+///
+/// - `1`: I/O error or anything else not covered below
+/// - `2`: no (matching) NetWare partition found in the image
+/// - `3`: the requested volume doesn't exist, the volume segment table
+///   can't be assembled unambiguously, or a directory id given as `0`
+///   can't be told apart from the root
+/// - `4`: directory or volume-header corruption
+/// - `5`: FAT corruption
+/// - `6`: image truncated or too small to hold what it claims to
+/// - `7`: an NSS/NetWare 5+ volume, which this tool doesn't parse
+fn exit_code_for(err: &nwfs::NwfsError) -> u8 {
+    use nwfs::NwfsError::*;
+    match err {
+        NoPartitionTable(_) | InvalidPartitionIndex { .. } | NotNetWarePartition { .. } | NoNetWarePartitionFound => 2,
+        NoVolumeWithId { .. }
+        | AmbiguousVolumeName { .. }
+        | IncompleteVolumeSegments { .. }
+        | VolumeBlockCountMismatch { .. }
+        | TooManyVolumes { .. }
+        | InvalidSegmentOrder { .. }
+        | AmbiguousDirId { .. } => 3,
+        InvalidMagic | BlockOutOfRange { .. } | BlockSizeMismatch { .. } | ZeroBlockSize { .. } => 4,
+        FatCorrupt { .. } => 5,
+        ImageTruncated { .. } | ImageTooSmall { .. } => 6,
+        UnsupportedNssVolume { .. } => 7,
+        Io { .. } | BlockIo { .. } | Other(_) => 1,
+    }
+}
+
+fn run(
+    command: Command,
+    lenient: bool,
+    ts_format: TimestampFormat,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    match command {
+        Command::Inspect {
+            image,
+            partition,
+            quiet,
+            dir_copy,
+            dump_fat,
+            extract_partition,
+        } => cmd_inspect(
+            &image,
+            selector(partition),
+            lenient,
+            quiet,
+            dir_copy,
+            dump_fat.as_deref(),
+            extract_partition.as_deref(),
+            vol_id,
+            block_size,
+            cache,
+            segments,
+        ),
+        Command::Shell {
+            image,
+            partition,
+            dir_copy,
+            script,
+            root,
+        } => cmd_shell(
+            &image,
+            selector(partition),
+            lenient,
+            dir_copy,
+            ts_format,
+            script.as_deref(),
+            root,
+            vol_id,
+            block_size,
+            cache,
+            segments,
+        ),
+        Command::Extract {
+            image,
+            dest,
+            partition,
+            at_block,
+            preserve_attrs,
+            preserve_case,
+            include_slack,
+            with_volume,
+        } => cmd_extract(
+            &image,
+            &dest,
+            selector(partition),
+            lenient,
+            at_block,
+            preserve_attrs,
+            preserve_case,
+            include_slack,
+            with_volume,
+            vol_id,
+            block_size,
+            cache,
+            segments,
+        ),
+        Command::ExtractTree {
+            image,
+            dest,
+            partition,
+            preserve_attrs,
+            preserve_case,
+            resume,
+        } => cmd_extract_tree(
+            &image,
+            &dest,
+            selector(partition),
+            lenient,
+            preserve_attrs,
+            preserve_case,
+            resume,
+            vol_id,
+            block_size,
+            cache,
+            segments,
+        ),
+        Command::Ls {
+            image,
+            partition,
+            long,
+            dir_copy,
+        } => cmd_ls(&image, selector(partition), long, lenient, dir_copy, ts_format, vol_id, block_size, cache, segments),
+        Command::Cat { image, name, partition } => cmd_cat(&image, &name, selector(partition), lenient, vol_id, block_size, cache, segments),
+        Command::Verify { image, partition } => cmd_verify(&image, selector(partition), lenient, vol_id, block_size, cache, segments),
+        Command::DumpPartition { image, out, partition } => cmd_dump_partition(&image, &out, selector(partition)),
+        Command::Fingerprint { image, partition } => cmd_fingerprint(&image, selector(partition), lenient, vol_id, block_size, cache, segments),
+        Command::Manifest {
+            image,
+            partition,
+            format,
+            deleted,
+        } => cmd_manifest(&image, selector(partition), lenient, format, deleted, ts_format, vol_id, block_size, cache, segments),
+        Command::Diff {
+            image,
+            other,
+            partition,
+            other_partition,
+            format,
+        } => cmd_diff(&image, &other, selector(partition), selector(other_partition), lenient, format, vol_id, block_size, cache, segments),
+        Command::MirrorVerify {
+            image,
+            other,
+            partition,
+            other_partition,
+        } => cmd_mirror_verify(&image, &other, selector(partition), selector(other_partition), lenient, vol_id, block_size, cache, segments),
+        Command::Undelete {
+            image,
+            name,
+            partition,
+            into,
+            write,
+        } => cmd_undelete(&image, &name, selector(partition), lenient, into, write, vol_id, block_size, cache, segments),
+        Command::Du {
+            image,
+            partition,
+            dir,
+            allocated,
+        } => cmd_du(&image, selector(partition), lenient, dir, allocated, vol_id, block_size, cache, segments),
+        Command::Orphans { image, partition, extract } => {
+            cmd_orphans(&image, selector(partition), lenient, extract.as_deref(), vol_id, block_size, cache, segments)
+        }
+        Command::Owners { image, partition, dir } => cmd_owners(&image, selector(partition), lenient, dir, vol_id, block_size, cache, segments),
+        Command::Map { image, partition, width } => {
+            cmd_map(&image, selector(partition), lenient, width, vol_id, block_size, cache, segments)
+        }
+        Command::Syslogs {
+            image,
+            partition,
+            extract,
+        } => cmd_syslogs(&image, selector(partition), lenient, extract.as_deref(), vol_id, block_size, cache, segments),
+        Command::Volumes { image, partition } => cmd_volumes(&image, selector(partition)),
+    }
+}
+
+fn warn(warnings: &[String]) {
+    for w in warnings {
+        eprintln!("warning: {w}");
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_inspect(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    quiet: bool,
+    dir_copy: u8,
+    dump_fat: Option<&std::path::Path>,
+    extract_partition: Option<&std::path::Path>,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let session = open_session_dir_copy(&image_str, sel, lenient, dir_copy, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+    println!(
+        "partition {}: type=0x{:02x}, format={:?}",
+        session.partition.index, session.partition.partition_type, session.format
+    );
+    println!("{} (dir_copy={dir_copy})", session.vol);
+    if let Some(path) = dump_fat {
+        write_fat_csv(&session.fat, path)?;
+    }
+    if let Some(out) = extract_partition {
+        dump_partition_to(image, session.partition, out)?;
+    }
+    if !quiet {
+        print_listing(&session.dir_entries);
+    }
+    Ok(())
+}
+
+/// Write one `entry_index,next_block` row per FAT entry to `path`, for
+/// offline analysis of allocation and chains in a spreadsheet.
+fn write_fat_csv(fat: &[u32], path: &std::path::Path) -> nwfs::Result<()> {
+    use std::io::Write as _;
+    let mut out = std::fs::File::create(path).map_err(|source| nwfs::NwfsError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    writeln!(out, "entry_index,next_block").ok();
+    for (index, next) in fat.iter().enumerate() {
+        writeln!(out, "{index},{next}").ok();
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_ls(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    long: bool,
+    lenient: bool,
+    dir_copy: u8,
+    ts_format: TimestampFormat,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let session = open_session_dir_copy(&image_str, sel, lenient, dir_copy, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+    if long {
+        print_listing_long(&session.dir_entries, ts_format);
+    } else {
+        print_listing(&session.dir_entries);
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_cat(
+    image: &std::path::Path,
+    name: &str,
+    sel: PartitionSelector,
+    lenient: bool,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+    let item = session.find_file(name)?;
+    let data = session.read_file(&item)?;
+    io::stdout().write_all(&data).ok();
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_extract(
+    image: &std::path::Path,
+    dest: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    at_block: Option<u32>,
+    preserve_attrs: bool,
+    preserve_case: bool,
+    include_slack: bool,
+    with_volume: bool,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+    let dest = if with_volume {
+        dest.join(&session.vol.info.name)
+    } else {
+        dest.to_path_buf()
+    };
+    let dest = dest.as_path();
+    std::fs::create_dir_all(dest).map_err(|source| nwfs::NwfsError::Io {
+        path: dest.into(),
+        source,
+    })?;
+
+    let entries = match at_block {
+        Some(block) => session.read_directory_at(block)?,
+        None => session.dir_entries.clone(),
+    };
+    let files: Vec<_> = entries
+        .iter()
+        .filter_map(|e| match e {
+            DirEntry::File(f) => Some(f.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for item in files {
+        let warned_so_far = session.warnings.len();
+        let data = if include_slack {
+            let (data, slack) = session.read_file_with_slack(&item)?;
+            if slack > 0 {
+                println!("'{}': {slack} slack byte(s) appended", item.name);
+            }
+            data
+        } else {
+            session.read_file(&item)?
+        };
+        warn(&session.warnings[warned_so_far..]);
+        let host_name = extracted_name(&item, preserve_case);
+        let out_path = dest.join(sanitize_host_component(&host_name));
+        std::fs::write(&out_path, &data).map_err(|source| nwfs::NwfsError::Io {
+            path: out_path.clone(),
+            source,
+        })?;
+        if preserve_attrs {
+            apply_attrs(&out_path, item.attr)?;
+        }
+        apply_timestamps(&out_path, &item)?;
+        println!("extracted {}", host_name);
+    }
+    Ok(())
+}
+
+/// Like [`cmd_extract`], but walks the whole tree via [`Session::file_tree`]
+/// and keeps going when a file fails instead of aborting -- a single bad
+/// block shouldn't cost you every file after it in the listing. Failures
+/// are recorded to `dest/.failed` so a later `--resume` run only retries
+/// what didn't make it.
+#[allow(clippy::too_many_arguments)]
+fn cmd_extract_tree(
+    image: &std::path::Path,
+    dest: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    preserve_attrs: bool,
+    preserve_case: bool,
+    resume: bool,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let files = session.file_tree()?;
+    let mut extracted = 0usize;
+    let mut failures: Vec<(String, Option<u32>, nwfs::NwfsError)> = Vec::new();
+
+    for (path, item) in files {
+        let out_path = extract_tree_host_path(dest, &path, &item, preserve_case);
+
+        if resume {
+            if let Ok(meta) = std::fs::metadata(&out_path) {
+                if meta.len() == item.length as u64 {
+                    continue;
+                }
+            }
+        }
+
+        let warned_so_far = session.warnings.len();
+        match session.read_file(&item) {
+            Ok(data) => {
+                warn(&session.warnings[warned_so_far..]);
+                if let Some(parent) = out_path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|source| nwfs::NwfsError::Io {
+                        path: parent.to_path_buf(),
+                        source,
+                    })?;
+                }
+                if let Err(source) = std::fs::write(&out_path, &data) {
+                    let err = nwfs::NwfsError::Io {
+                        path: out_path.clone(),
+                        source,
+                    };
+                    eprintln!("warning: failed to write '{path}': {err}");
+                    failures.push((path, None, err));
+                    continue;
+                }
+                if preserve_attrs {
+                    apply_attrs(&out_path, item.attr)?;
+                }
+                apply_timestamps(&out_path, &item)?;
+                println!("extracted {path}");
+                extracted += 1;
+            }
+            Err(err) => {
+                eprintln!("warning: failed to extract '{path}': {err}");
+                let block = error_block_hint(&err);
+                failures.push((path, block, err));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        write_failed_manifest(&dest.join(".failed"), &failures)?;
+    }
+    println!("{extracted} extracted, {} failed", failures.len());
+    Ok(())
+}
+
+/// Make an on-disk name safe to use as a single host path component.
+/// NetWare name fields are raw on-disk bytes ([`nwfs::bytes::ascii_name`]
+/// only drops embedded NULs) -- a corrupt or hostile one reading as e.g.
+/// `..`, `/etc/passwd`, or (on Windows) `C:foo` would otherwise reach
+/// `PathBuf::push`/`Path::join` unchanged. Since those replace the path
+/// built so far outright when the pushed component looks absolute or
+/// carries a drive prefix, and walk back up it for `..`, that's a
+/// zip-slip-class escape out of `dest` for a tool whose entire purpose is
+/// processing untrusted images. Replacing every path separator and drive
+/// letter colon neutralizes all of that: what's left can't look absolute
+/// and can't carry a prefix, and `..` only survives as a literal two-dot
+/// name once separators are gone, so it's caught by the exact-match
+/// fallback below.
+fn sanitize_host_component(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| if c == '/' || c == '\\' || c == ':' { '_' } else { c })
+        .collect();
+    match replaced.as_str() {
+        "" | "." | ".." => "_".to_string(),
+        _ => replaced,
+    }
+}
+
+/// Host path for the file at `path` (its full path inside the volume,
+/// `/`-joined) under `dest`, with [`extracted_name`]'s case handling applied
+/// to the leaf component only -- intermediate directories keep the names
+/// already recorded in the directory tree. Every component is passed
+/// through [`sanitize_host_component`] before it reaches the host path, so
+/// a corrupt or hostile name anywhere in the chain can't write outside
+/// `dest`.
+fn extract_tree_host_path(
+    dest: &std::path::Path,
+    path: &str,
+    item: &nwfs::dirent::FileItem,
+    preserve_case: bool,
+) -> std::path::PathBuf {
+    let mut out = dest.to_path_buf();
+    let mut components = path.split('/').peekable();
+    while let Some(component) = components.next() {
+        if components.peek().is_none() {
+            out.push(sanitize_host_component(&extracted_name(item, preserve_case)));
+        } else {
+            out.push(sanitize_host_component(component));
+        }
+    }
+    out
+}
+
+/// The block number behind an extraction failure, when the error carries
+/// one -- for a `.failed` manifest entry that a human can cross-reference
+/// against `nwfs inspect --dump-fat` without re-parsing the error message.
+fn error_block_hint(err: &nwfs::NwfsError) -> Option<u32> {
+    match err {
+        nwfs::NwfsError::BlockIo { block, .. } => Some(*block),
+        nwfs::NwfsError::FatCorrupt { offset } => Some((*offset / 4) as u32),
+        _ => None,
+    }
+}
+
+/// Write one `path,block,error` row per failed file to `path`, mirroring
+/// [`write_fat_csv`]'s style -- `block` is empty when the error doesn't
+/// name one.
+fn write_failed_manifest(
+    path: &std::path::Path,
+    failures: &[(String, Option<u32>, nwfs::NwfsError)],
+) -> nwfs::Result<()> {
+    use std::io::Write as _;
+    let mut out = std::fs::File::create(path).map_err(|source| nwfs::NwfsError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    writeln!(out, "path,block,error").ok();
+    for (file_path, block, err) in failures {
+        let block = block.map(|b| b.to_string()).unwrap_or_default();
+        writeln!(out, "{file_path},{block},{err}").ok();
+    }
+    Ok(())
+}
+
+/// Host filename to extract `item` as. With `--preserve-case`, this should
+/// prefer the long-name namespace entry's original casing over the
+/// uppercased 8.3 name; this crate doesn't parse namespace entries yet, so
+/// `long_name_for` always returns `None` and every file falls back to its
+/// 8.3 name regardless of `preserve_case`.
+fn extracted_name(item: &nwfs::dirent::FileItem, preserve_case: bool) -> String {
+    if preserve_case {
+        if let Some(long_name) = long_name_for(item) {
+            return long_name;
+        }
+    }
+    item.name.clone()
+}
+
+/// Placeholder for a future long-name namespace lookup. Always `None` until
+/// this crate parses NetWare namespace entries.
+fn long_name_for(_item: &nwfs::dirent::FileItem) -> Option<String> {
+    None
+}
+
+/// Map NetWare attribute bits onto host file permissions. Currently just
+/// `ATTR_READONLY` -> clearing the owner-write bit; `HIDDEN`/`SYSTEM` have
+/// no Unix permission-bit equivalent and are left alone.
+#[cfg(unix)]
+fn apply_attrs(path: &std::path::Path, attr: u16) -> nwfs::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if attr & nwfs::dirent::attr::READ_ONLY == 0 {
+        return Ok(());
+    }
+    let mut perms = std::fs::metadata(path)
+        .map_err(|source| nwfs::NwfsError::Io {
+            path: path.into(),
+            source,
+        })?
+        .permissions();
+    perms.set_mode(perms.mode() & !0o200);
+    std::fs::set_permissions(path, perms).map_err(|source| nwfs::NwfsError::Io {
+        path: path.into(),
+        source,
+    })
+}
+
+#[cfg(not(unix))]
+fn apply_attrs(_path: &std::path::Path, _attr: u16) -> nwfs::Result<()> {
+    Ok(())
+}
+
+/// Set an extracted file's modified (and, where supported, created) time
+/// from its NetWare directory entry, instead of leaving the host
+/// filesystem's time-of-extraction -- the point of archiving is keeping
+/// the original metadata, not when someone happened to pull the file off
+/// the image. A zero/unset `modify_time` is left alone rather than
+/// treated as an error, since that's routine for some record types, not
+/// corruption.
+#[cfg(windows)]
+fn apply_timestamps(path: &std::path::Path, item: &nwfs::dirent::FileItem) -> nwfs::Result<()> {
+    use std::os::windows::fs::FileTimesExt;
+
+    let Some(modified) = item.modify_time.to_system_time() else {
+        return Ok(());
+    };
+    let file = std::fs::File::options().write(true).open(path).map_err(|source| nwfs::NwfsError::Io {
+        path: path.into(),
+        source,
+    })?;
+
+    let times = std::fs::FileTimes::new().set_modified(modified);
+    let times = match item.create_time.to_system_time() {
+        Some(created) => times.set_created(created),
+        None => times,
+    };
+    file.set_times(times).map_err(|source| nwfs::NwfsError::Io {
+        path: path.into(),
+        source,
+    })
+}
+
+/// Unix has no syscall for setting a file's birth time at all (most
+/// filesystems don't even track one), so `create_time` has nowhere to
+/// go here -- `modify_time` is the one timestamp this platform can
+/// actually preserve.
+#[cfg(not(windows))]
+fn apply_timestamps(path: &std::path::Path, item: &nwfs::dirent::FileItem) -> nwfs::Result<()> {
+    let Some(modified) = item.modify_time.to_system_time() else {
+        return Ok(());
+    };
+    let file = std::fs::File::options().write(true).open(path).map_err(|source| nwfs::NwfsError::Io {
+        path: path.into(),
+        source,
+    })?;
+    file.set_times(std::fs::FileTimes::new().set_modified(modified))
+        .map_err(|source| nwfs::NwfsError::Io {
+            path: path.into(),
+            source,
+        })
+}
+
+fn cmd_dump_partition(image: &std::path::Path, out: &std::path::Path, sel: PartitionSelector) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut images = nwfs::ImageList::new();
+    images.add_image(&image_str)?;
+    let partition = *images.select_partition(sel)?;
+
+    dump_partition_to(image, partition, out)?;
+    println!("wrote {} bytes to {}", partition.byte_len(), out.display());
+    Ok(())
+}
+
+/// Copy exactly the bytes of `partition` (using its MBR-reported start LBA
+/// and sector count) out of `image` into `out`, shared by `dump-partition`
+/// and `inspect --extract-partition`.
+fn dump_partition_to(image: &std::path::Path, partition: nwfs::mbr::PartitionEntry, out: &std::path::Path) -> nwfs::Result<()> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut src = nwfs::source::open_source(image)?;
+    src.seek(SeekFrom::Start(partition.byte_offset()))
+        .map_err(|source| nwfs::NwfsError::Io {
+            path: image.into(),
+            source,
+        })?;
+
+    let mut dest = std::fs::File::create(out).map_err(|source| nwfs::NwfsError::Io {
+        path: out.into(),
+        source,
+    })?;
+
+    let mut remaining = partition.byte_len();
+    let mut buf = [0u8; 64 * 1024];
+    while remaining > 0 {
+        let take = remaining.min(buf.len() as u64) as usize;
+        src.read_exact(&mut buf[..take]).map_err(|source| nwfs::NwfsError::Io {
+            path: image.into(),
+            source,
+        })?;
+        dest.write_all(&buf[..take]).map_err(|source| nwfs::NwfsError::Io {
+            path: out.into(),
+            source,
+        })?;
+        remaining -= take as u64;
+    }
+    Ok(())
+}
+
+fn cmd_volumes(image: &std::path::Path, sel: PartitionSelector) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut images = nwfs::ImageList::new();
+    images.add_image(&image_str)?;
+    let partition = *images.select_partition(sel)?;
+
+    let mut file = nwfs::source::open_source(image)?;
+    let (entries, warnings) = nwfs::voltab::read_volume_table(&mut file, &partition)?;
+    warn(&warnings);
+
+    let mut volumes = nwfs::voltab::list_volumes(&entries);
+    volumes.sort_by_key(|v| v.volume_number);
+    for v in &volumes {
+        println!(
+            "volume_number={} name='{}' segments={} total_sectors={}",
+            v.volume_number, v.name, v.num_segments, v.total_sectors
+        );
+    }
+    Ok(())
+}
+
+fn cmd_verify(image: &std::path::Path, sel: PartitionSelector, lenient: bool, vol_id: Option<u32>, block_size: Option<u32>, cache: bool, segments: Option<&[u32]>) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    if session.hotfix.entries().is_empty() {
+        println!("no hotfix-redirected blocks on this volume");
+    }
+    for entry in &session.dir_entries {
+        if let DirEntry::File(item) = entry {
+            let report = session.verify_file(item)?;
+            if report.redirected_blocks > 0 {
+                println!(
+                    "{}: {}/{} block(s) hotfix-redirected",
+                    item.name, report.redirected_blocks, report.total_blocks
+                );
+            }
+        }
+    }
+
+    let cross_linked = session.cross_linked_blocks()?;
+    if cross_linked.is_empty() {
+        println!("no cross-linked blocks found");
+    } else {
+        for c in &cross_linked {
+            println!(
+                "CROSS-LINKED: block {} claimed by {} file(s): {}",
+                c.block,
+                c.paths.len(),
+                c.paths.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+fn cmd_fingerprint(image: &std::path::Path, sel: PartitionSelector, lenient: bool, vol_id: Option<u32>, block_size: Option<u32>, cache: bool, segments: Option<&[u32]>) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let digest = session.content_fingerprint()?;
+    let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+    println!("{hex}");
+    Ok(())
+}
+
+enum FileDiff {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl FileDiff {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FileDiff::Added => "added",
+            FileDiff::Removed => "removed",
+            FileDiff::Changed => "changed",
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_diff(
+    image: &std::path::Path,
+    other: &std::path::Path,
+    sel: PartitionSelector,
+    other_sel: PartitionSelector,
+    lenient: bool,
+    format: DiffFormat,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let other_str = other.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    let mut other_session = open_session(&other_str, other_sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+    warn(&other_session.warnings);
+
+    let files = session.file_tree()?;
+    let other_files: std::collections::BTreeMap<String, nwfs::dirent::FileItem> = other_session.file_tree()?.into_iter().collect();
+    let mut other_remaining = other_files.clone();
+
+    let mut diffs = Vec::new();
+    for (path, item) in &files {
+        match other_files.get(path) {
+            None => diffs.push((path.clone(), FileDiff::Removed)),
+            Some(other_item) => {
+                other_remaining.remove(path);
+                let changed = if item.length != other_item.length {
+                    true
+                } else {
+                    session.file_fingerprint(item)? != other_session.file_fingerprint(other_item)?
+                };
+                if changed {
+                    diffs.push((path.clone(), FileDiff::Changed));
+                }
+            }
+        }
+    }
+    for path in other_remaining.keys() {
+        diffs.push((path.clone(), FileDiff::Added));
+    }
+    diffs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    match format {
+        DiffFormat::Text => {
+            if diffs.is_empty() {
+                println!("no differences");
+            }
+            for (path, kind) in &diffs {
+                println!("{} {path}", kind.as_str());
+            }
+        }
+        DiffFormat::Json => {
+            println!("[");
+            for (i, (path, kind)) in diffs.iter().enumerate() {
+                println!(
+                    "  {{\"path\": \"{}\", \"change\": \"{}\"}}{}",
+                    json_escape(path),
+                    kind.as_str(),
+                    if i + 1 < diffs.len() { "," } else { "" }
+                );
+            }
+            println!("]");
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_mirror_verify(
+    image: &std::path::Path,
+    other: &std::path::Path,
+    sel: PartitionSelector,
+    other_sel: PartitionSelector,
+    lenient: bool,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let other_str = other.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    let mut other_session = open_session(&other_str, other_sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+    warn(&other_session.warnings);
+
+    let report = session.mirror_verify(&mut other_session)?;
+    println!("compared {} block(s)", report.blocks_compared);
+    match report.first_divergent_block {
+        None => println!("no divergence: both images agree over the compared range"),
+        Some(first) => println!(
+            "first divergent block: {first}\n{} block(s) of {} mismatched",
+            report.mismatched_blocks, report.blocks_compared
+        ),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_undelete(
+    image: &std::path::Path,
+    name: &str,
+    sel: PartitionSelector,
+    lenient: bool,
+    into_dir_id: u32,
+    write: bool,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    if !write {
+        let (_, item) = session.find_deleted_file(name)?;
+        let data = session.read_file(&item)?;
+        println!(
+            "'{name}' found (dir_id={}, {} byte(s)), chain reads back cleanly",
+            item.dir_id,
+            data.len()
+        );
+        println!("dry run: pass --write to actually restore it under dir_id {into_dir_id}");
+        return Ok(());
+    }
+
+    eprintln!("warning: modifying '{}' in place -- this cannot be undone", image.display());
+    let restored = session.undelete(name, into_dir_id)?;
+    println!(
+        "restored '{}' under dir_id {into_dir_id} ({} byte(s))",
+        restored.name, restored.length
+    );
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_du(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    dir: Option<u32>,
+    allocated: bool,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let (per_dir, total) = session.du(dir, allocated)?;
+    for (name, size) in &per_dir {
+        println!("{size:>12}  {name}");
+    }
+    println!("{total:>12}  total");
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_owners(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    dir: Option<u32>,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let summaries = session.owners(dir)?;
+    for s in &summaries {
+        println!("0x{:04x}  {:>6} file(s)  {:>12} byte(s)", s.owner_id, s.file_count, s.total_bytes);
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_map(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    width: usize,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let states = session.block_map()?;
+    let width = width.max(1);
+    let (mut used, mut free, mut bad) = (0usize, 0usize, 0usize);
+    for (i, chunk) in states.chunks(width).enumerate() {
+        let row: String = chunk
+            .iter()
+            .map(|s| match s {
+                BlockState::Used => {
+                    used += 1;
+                    '#'
+                }
+                BlockState::Free => {
+                    free += 1;
+                    '.'
+                }
+                BlockState::Bad => {
+                    bad += 1;
+                    'X'
+                }
+            })
+            .collect();
+        println!("{:>10}  {row}", i * width);
+    }
+    println!();
+    println!("used={used} free={free} bad={bad} total={}", states.len());
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_syslogs(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    extract: Option<&std::path::Path>,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let logs = session.syslogs()?;
+    if logs.is_empty() {
+        println!("no known system logs found");
+        return Ok(());
+    }
+    if let Some(dest) = extract {
+        std::fs::create_dir_all(dest).map_err(|source| nwfs::NwfsError::Io {
+            path: dest.into(),
+            source,
+        })?;
+    }
+    for (path, item) in &logs {
+        println!("{path} ({} byte(s))", item.length);
+        if let Some(dest) = extract {
+            let data = session.read_file(item)?;
+            let out_path = dest.join(&item.name);
+            std::fs::write(&out_path, &data).map_err(|source| nwfs::NwfsError::Io {
+                path: out_path.clone(),
+                source,
+            })?;
+            apply_timestamps(&out_path, item)?;
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_orphans(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    extract: Option<&std::path::Path>,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let orphans = session.orphans()?;
+    if orphans.is_empty() {
+        println!("no orphaned entries found");
+        return Ok(());
+    }
+    for (missing_parent, entries) in &orphans {
+        println!("missing parent {missing_parent}: {} entrie(s)", entries.len());
+        for (path, entry) in entries {
+            match entry {
+                DirEntry::File(f) => println!("  {path} ({} bytes)", f.length),
+                DirEntry::Directory(_) => println!("  {path} <DIR>"),
+            }
+        }
+    }
+
+    let Some(dest) = extract else {
+        return Ok(());
+    };
+    for (missing_parent, entries) in &orphans {
+        let group_dest = dest.join(missing_parent.to_string());
+        std::fs::create_dir_all(&group_dest).map_err(|source| nwfs::NwfsError::Io {
+            path: group_dest.clone(),
+            source,
+        })?;
+        for (_, entry) in entries {
+            let DirEntry::File(item) = entry else {
+                continue;
+            };
+            let data = session.read_file(item)?;
+            let out_path = group_dest.join(&item.name);
+            std::fs::write(&out_path, &data).map_err(|source| nwfs::NwfsError::Io {
+                path: out_path.clone(),
+                source,
+            })?;
+            apply_timestamps(&out_path, item)?;
+            println!("extracted {} -> {}", item.name, out_path.display());
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_manifest(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    format: ManifestFormat,
+    deleted: DeletedFilter,
+    ts_format: TimestampFormat,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session(&image_str, sel, lenient, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+
+    let entries = session.manifest(deleted)?;
+    match format {
+        ManifestFormat::Csv => print_manifest_csv(&entries, ts_format),
+        ManifestFormat::Json => print_manifest_json(&entries, ts_format),
+    }
+    Ok(())
+}
+
+fn print_manifest_csv(entries: &[(String, DirEntry)], ts_format: TimestampFormat) {
+    println!("path,type,length,attr,attr_bits,created,modified,deleted");
+    for (path, entry) in entries {
+        let (kind, length) = match entry {
+            DirEntry::File(f) => ("file", f.length),
+            DirEntry::Directory(_) => ("dir", 0),
+        };
+        println!(
+            "{},{kind},{length},{},0x{:04x},{},{},{}",
+            csv_field(path),
+            entry.attributes(),
+            entry.attributes().bits(),
+            entry.create_time().format(ts_format),
+            entry.modify_time().format(ts_format),
+            entry.is_deleted()
+        );
+    }
+}
+
+/// Quote a CSV field if it contains a comma, double quote, or newline,
+/// doubling any embedded quotes -- the standard RFC 4180 escaping.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn print_manifest_json(entries: &[(String, DirEntry)], ts_format: TimestampFormat) {
+    println!("[");
+    for (i, (path, entry)) in entries.iter().enumerate() {
+        let (kind, length) = match entry {
+            DirEntry::File(f) => ("file", f.length),
+            DirEntry::Directory(_) => ("dir", 0),
+        };
+        println!(
+            "  {{\"path\": \"{}\", \"type\": \"{kind}\", \"length\": {length}, \"attr\": \"{}\", \"attr_bits\": {}, \"created\": \"{}\", \"modified\": \"{}\", \"deleted\": {}}}{}",
+            json_escape(path),
+            entry.attributes(),
+            entry.attributes().bits(),
+            entry.create_time().format(ts_format),
+            entry.modify_time().format(ts_format),
+            entry.is_deleted(),
+            if i + 1 < entries.len() { "," } else { "" }
+        );
+    }
+    println!("]");
+}
+
+/// Escape a string for embedding in a JSON string literal.
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Outcome of dispatching a single shell command line, so both the
+/// interactive loop and script mode can share one dispatcher.
+enum ShellOutcome {
+    Continue,
+    Quit,
+    Error(String),
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_shell(
+    image: &std::path::Path,
+    sel: PartitionSelector,
+    lenient: bool,
+    dir_copy: u8,
+    ts_format: TimestampFormat,
+    script: Option<&std::path::Path>,
+    root: Option<u32>,
+    vol_id: Option<u32>,
+    block_size: Option<u32>,
+    cache: bool,
+    segments: Option<&[u32]>,
+) -> nwfs::Result<()> {
+    let image_str = image.to_string_lossy().into_owned();
+    let mut session = open_session_dir_copy(&image_str, sel, lenient, dir_copy, vol_id, block_size, cache, segments)?;
+    warn(&session.warnings);
+    if let Some(root) = root {
+        session.cd(root)?;
+    }
+
+    match script {
+        Some(path) => {
+            let file = std::fs::File::open(path).map_err(|source| nwfs::NwfsError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            run_shell_loop(&mut session, ts_format, &mut io::BufReader::new(file), false);
+        }
+        None => {
+            println!(
+                "nwfs shell ({:?}) -- volume '{}', type 'quit' to exit",
+                session.format, session.vol.info.name
+            );
+            if io::stdin().is_terminal() {
+                run_shell_repl(&mut session, ts_format);
+            } else {
+                // Piped input with no line editor attached: fall back to
+                // the plain reader rather than making rustyline guess at a
+                // non-tty terminal.
+                run_shell_loop(&mut session, ts_format, &mut io::stdin().lock(), true);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Completes shell command names for the first word, and the names of
+/// entries in the currently loaded directory for every later word,
+/// whichever command precedes it.
+struct ShellCompleter {
+    commands: Vec<String>,
+    names: Vec<String>,
+}
+
+impl ShellCompleter {
+    fn new(session: &Session) -> Self {
+        Self {
+            commands: vec![
+                "dir".into(),
+                "ls".into(),
+                "find".into(),
+                "cat".into(),
+                "get".into(),
+                "cd".into(),
+                "salvage".into(),
+                "quit".into(),
+                "exit".into(),
+            ],
+            names: session.dir_entries.iter().map(|e| e.name().to_string()).collect(),
+        }
+    }
+}
+
+impl Completer for ShellCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos].rfind(' ').map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..pos];
+        let is_first_word = start == 0;
+        let pool = if is_first_word { &self.commands } else { &self.names };
+        let candidates = pool
+            .iter()
+            .filter(|candidate| candidate.to_lowercase().starts_with(&word.to_lowercase()))
+            .cloned()
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for ShellCompleter {}
+
+impl Validator for ShellCompleter {}
+
+impl Helper for ShellCompleter {}
+
+/// Interactive shell loop backed by `rustyline`: history, line editing, and
+/// tab-completion of command and entry names. Used only when stdin is an
+/// actual terminal; piped input falls back to [`run_shell_loop`] instead.
+fn run_shell_repl(session: &mut Session, ts_format: TimestampFormat) {
+    let completer = ShellCompleter::new(session);
+    let mut editor = match Editor::<ShellCompleter, rustyline::history::DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            eprintln!("warning: couldn't start the line editor ({err}); falling back to plain input");
+            run_shell_loop(session, ts_format, &mut io::stdin().lock(), true);
+            return;
+        }
+    };
+    editor.set_helper(Some(completer));
+
+    loop {
+        match editor.readline("> ") {
+            Ok(line) => {
+                editor.add_history_entry(line.as_str()).ok();
+                let outcome = run_shell_command(session, ts_format, &line);
+                // `cd` may have swapped in a different directory's entries;
+                // keep tab-completion in sync rather than completing names
+                // from whatever directory the shell started in.
+                if let Some(helper) = editor.helper_mut() {
+                    helper.names = session.dir_entries.iter().map(|e| e.name().to_string()).collect();
+                }
+                match outcome {
+                    ShellOutcome::Continue => {}
+                    ShellOutcome::Quit => {
+                        io::stdout().flush().ok();
+                        break;
+                    }
+                    ShellOutcome::Error(msg) => println!("{msg}"),
+                }
+            }
+            Err(ReadlineError::Eof) => {
+                println!("\nbye");
+                break;
+            }
+            Err(ReadlineError::Interrupted) => break,
+            Err(err) => {
+                eprintln!("error: {err}");
+                break;
+            }
+        }
+    }
+}
+
+/// Run commands from `input` until EOF or `quit`/`exit`. In interactive
+/// mode a `> ` prompt is printed before each read; in script mode the
+/// prompt is suppressed, every line's error (if any) is reported with its
+/// line number, and a pass/fail summary is printed at the end instead of
+/// stopping at the first failure.
+fn run_shell_loop(session: &mut Session, ts_format: TimestampFormat, input: &mut dyn BufRead, interactive: bool) {
+    let mut line_num = 0u32;
+    let mut failed = 0u32;
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush().ok();
+        }
+        let mut line = String::new();
+        if input.read_line(&mut line).unwrap_or(0) == 0 {
+            if interactive {
+                println!("\nbye");
+            }
+            break;
+        }
+        line_num += 1;
+        if !interactive && line.trim().is_empty() {
+            continue;
+        }
+        match run_shell_command(session, ts_format, &line) {
+            ShellOutcome::Continue => {}
+            ShellOutcome::Quit => {
+                io::stdout().flush().ok();
+                break;
+            }
+            ShellOutcome::Error(msg) => {
+                failed += 1;
+                if interactive {
+                    println!("{msg}");
+                } else {
+                    eprintln!("line {line_num}: {msg}");
+                }
+            }
+        }
+    }
+    if !interactive {
+        println!("{line_num} command(s) run, {failed} failed");
+    }
+}
+
+/// Dispatch a single shell command line against `session`, printing its
+/// output directly. Returns [`ShellOutcome::Error`] for a recognized
+/// command that failed, so script mode can count it without the dispatcher
+/// needing to know whether it's running interactively.
+fn run_shell_command(session: &mut Session, ts_format: TimestampFormat, line: &str) -> ShellOutcome {
+    let tokens = split_shell_line(line);
+    let mut parts = tokens.iter().map(String::as_str);
+    match parts.next() {
+        None => ShellOutcome::Continue,
+        Some("quit") | Some("exit") => ShellOutcome::Quit,
+        Some("dir") | Some("ls") => {
+            let mut long = false;
+            let mut pattern = None;
+            for tok in parts {
+                if tok == "-l" || tok == "--created" {
+                    long = true;
+                } else {
+                    pattern = Some(tok);
+                }
+            }
+            let Some(pattern) = pattern else {
+                if long {
+                    print_listing_long(&session.dir_entries, ts_format);
+                } else {
+                    print_listing(&session.dir_entries);
+                }
+                return ShellOutcome::Continue;
+            };
+            match session.list_matching_dirs(pattern) {
+                Ok(matches) if matches.is_empty() => {
+                    println!("no directories match '{pattern}'");
+                    ShellOutcome::Continue
+                }
+                Ok(matches) => {
+                    for (path, entries) in matches {
+                        println!("{}:", if path.is_empty() { "." } else { &path });
+                        if long {
+                            print_listing_long(&entries, ts_format);
+                        } else {
+                            print_listing(&entries);
+                        }
+                    }
+                    ShellOutcome::Continue
+                }
+                Err(err) => ShellOutcome::Error(format!("error: {err}")),
+            }
+        }
+        Some("find") => {
+            let Some(needle) = parts.next() else {
+                return ShellOutcome::Error("usage: find <substring>".to_string());
+            };
+            match session.find_substring(needle) {
+                Ok(matches) if matches.is_empty() => {
+                    println!("no matches for '{needle}'");
+                    ShellOutcome::Continue
+                }
+                Ok(matches) => {
+                    for (path, entry) in matches {
+                        match entry {
+                            DirEntry::File(f) => println!("{path} ({} bytes)", f.length),
+                            DirEntry::Directory(_) => println!("{path} <DIR>"),
+                        }
+                    }
+                    ShellOutcome::Continue
+                }
+                Err(err) => ShellOutcome::Error(format!("error: {err}")),
+            }
+        }
+        Some("locate") => {
+            let Some(block) = parts.next() else {
+                return ShellOutcome::Error("usage: locate <block>".to_string());
+            };
+            let block = match block.parse::<u32>() {
+                Ok(b) => b,
+                Err(_) => return ShellOutcome::Error(format!("invalid block number: '{block}'")),
+            };
+            match session.vol.block_to_offset(block) {
+                Ok(offset) => {
+                    println!("{}: offset 0x{offset:x} ({offset})", session.image_path);
+                    ShellOutcome::Continue
+                }
+                Err(err) => ShellOutcome::Error(format!("error: {err}")),
+            }
+        }
+        Some("segments") => {
+            let Some(name) = parts.next() else {
+                return ShellOutcome::Error("usage: segments <name>".to_string());
+            };
+            match session.find_file(name).and_then(|item| session.file_segments(&item)) {
+                Ok(segments) => {
+                    println!("{segments:?}");
+                    ShellOutcome::Continue
+                }
+                Err(err) => ShellOutcome::Error(format!("error: {err}")),
+            }
+        }
+        Some("cd") => {
+            let Some(arg) = parts.next() else {
+                return ShellOutcome::Error("usage: cd #<dir_id>".to_string());
+            };
+            let Some(id_str) = arg.strip_prefix('#') else {
+                return ShellOutcome::Error(format!("usage: cd #<dir_id> (got '{arg}')"));
+            };
+            let dir_id = match id_str.parse::<u32>() {
+                Ok(id) => id,
+                Err(_) => return ShellOutcome::Error(format!("invalid dir_id: '{id_str}'")),
+            };
+            match session.cd(dir_id) {
+                Ok(()) => {
+                    println!("now in directory #{dir_id} ({} entries)", session.dir_entries.len());
+                    ShellOutcome::Continue
+                }
+                Err(err) => ShellOutcome::Error(format!("error: {err}")),
+            }
+        }
+        Some("salvage") => {
+            let Some(dir_id) = parts.next() else {
+                return ShellOutcome::Error("usage: salvage <dir_id>".to_string());
+            };
+            let dir_id = match dir_id.parse::<u32>() {
+                Ok(id) => id,
+                Err(_) => return ShellOutcome::Error(format!("invalid dir_id: '{dir_id}'")),
+            };
+            match session.salvage_directory(dir_id) {
+                Ok(entries) => {
+                    print_listing(&entries);
+                    ShellOutcome::Continue
+                }
+                Err(err) => ShellOutcome::Error(format!("error: {err}")),
+            }
+        }
+        Some(cmd @ ("cat" | "get")) => {
+            let Some(name) = parts.next() else {
+                return ShellOutcome::Error(format!("usage: {cmd} <file>"));
+            };
+            match session.find_file(name).and_then(|item| session.read_file(&item)) {
+                Ok(data) => {
+                    io::stdout().write_all(&data).ok();
+                    println!();
+                    ShellOutcome::Continue
+                }
+                Err(err) => ShellOutcome::Error(format!("error: {err}")),
+            }
+        }
+        Some(other) => ShellOutcome::Error(format!("unknown command: {other}")),
+    }
+}
+
+/// Split a shell command line into tokens on whitespace, treating
+/// double-quoted spans (e.g. `cat "my file.txt"`) as a single token and
+/// ignoring leading/trailing whitespace.
+fn split_shell_line(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_token = false;
+
+    for c in line.trim().chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_token = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_token {
+                    tokens.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+    if has_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn print_listing(entries: &[DirEntry]) {
+    for e in entries {
+        let view = format_entry(e, TimestampFormat::default());
+        println!(
+            "{:<20} {}",
+            view.name,
+            if view.kind == EntryKind::Directory { "<DIR>" } else { "" }
+        );
+    }
+}
+
+/// Like [`print_listing`] but with create time, modify time, and owner id
+/// in fixed-width columns so they stay lined up across many entries.
+fn print_listing_long(entries: &[DirEntry], ts_format: TimestampFormat) {
+    for e in entries {
+        println!(
+            "{:<20} {:<5} {:<20} {:<20} owner={} modifier={}",
+            e.name(),
+            if e.is_directory() { "<DIR>" } else { "" },
+            e.create_time().format(ts_format),
+            e.modify_time().format(ts_format),
+            e.owner_id(),
+            e.modifier_id(),
+        );
+    }
+}
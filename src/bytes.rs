@@ -0,0 +1,36 @@
+//! Little-endian scalar readers for the on-disk structures. NetWare metadata
+//! was always written by x86 DOS/NetWare servers, so everything in this
+//! crate is little-endian regardless of the host's native order.
+
+pub fn u16_le(buf: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([buf[off], buf[off + 1]])
+}
+
+pub fn u32_le(buf: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([buf[off], buf[off + 1], buf[off + 2], buf[off + 3]])
+}
+
+pub fn u64_le(buf: &[u8], off: usize) -> u64 {
+    let mut arr = [0u8; 8];
+    arr.copy_from_slice(&buf[off..off + 8]);
+    u64::from_le_bytes(arr)
+}
+
+/// Decode a fixed-width on-disk name field as a lossy ASCII string, with
+/// any embedded NUL bytes (left over from a shorter name than the field's
+/// declared length, or from corruption) dropped rather than kept as part
+/// of the name.
+pub fn ascii_name(buf: &[u8]) -> String {
+    String::from_utf8_lossy(buf).into_owned().replace('\0', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_name_drops_embedded_nuls() {
+        assert_eq!(ascii_name(b"FOO\0\0\0\0\0\0\0\0\0"), "FOO");
+        assert_eq!(ascii_name(b"A\0B"), "AB");
+    }
+}
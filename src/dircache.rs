@@ -0,0 +1,184 @@
+//! A read-only, on-disk cache of a volume's parsed FAT and directory
+//! table, stored as a sidecar file next to the image. Opening a volume
+//! means walking its entire FAT and directory table from disk every
+//! time, which dominates the cost of opening a large one; a tool that
+//! reopens the same image repeatedly (a test suite, a long-running
+//! service re-scanning the same export) can skip that walk entirely once
+//! it's been cached once.
+//!
+//! The cache is keyed by everything [`Session::open_with_volume`] uses to
+//! decide *what* to read (the image's size and modification time, plus
+//! the directory copy, volume selector, and block size override that was
+//! passed in) -- any mismatch is treated as a miss rather than an attempt
+//! to patch up a cache built for a different request.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dirent::DirEntry;
+use crate::error::{NwfsError, Result};
+use crate::voltab::VolumeSelector;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheKey {
+    image_len: u64,
+    image_mtime_secs: u64,
+    image_mtime_nanos: u32,
+    dir_copy: u8,
+    volume_id: Option<u32>,
+    block_size_override: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    key: CacheKey,
+    fat: Vec<u32>,
+    dir_entries: Vec<DirEntry>,
+}
+
+/// Sidecar cache path for `image_path`: the image path with `.nwfs-cache`
+/// appended, so it sits next to the image without colliding with any
+/// plausible real extension the image itself might have.
+pub fn cache_path(image_path: &Path) -> PathBuf {
+    let mut name = image_path.as_os_str().to_owned();
+    name.push(".nwfs-cache");
+    PathBuf::from(name)
+}
+
+fn volume_id(volume_selector: VolumeSelector) -> Option<u32> {
+    match volume_selector {
+        VolumeSelector::Auto => None,
+        VolumeSelector::ById(id) => Some(id),
+    }
+}
+
+fn key_for(
+    image_path: &Path,
+    dir_copy: u8,
+    volume_selector: VolumeSelector,
+    block_size_override: Option<u32>,
+) -> Result<CacheKey> {
+    let meta = std::fs::metadata(image_path).map_err(|source| NwfsError::Io {
+        path: image_path.to_path_buf(),
+        source,
+    })?;
+    let mtime = meta.modified().map_err(|source| NwfsError::Io {
+        path: image_path.to_path_buf(),
+        source,
+    })?;
+    let since_epoch = mtime.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    Ok(CacheKey {
+        image_len: meta.len(),
+        image_mtime_secs: since_epoch.as_secs(),
+        image_mtime_nanos: since_epoch.subsec_nanos(),
+        dir_copy,
+        volume_id: volume_id(volume_selector),
+        block_size_override,
+    })
+}
+
+/// Load the cached FAT and directory table for `image_path`, if a sidecar
+/// cache exists at [`cache_path`] and its key still matches the image's
+/// current size/mtime and the request being made of it. Any miss,
+/// mismatch, or decode failure comes back as `None` rather than an error
+/// -- a missing, stale, or corrupt cache should never stop the volume from
+/// opening the normal way.
+pub fn load(
+    image_path: &Path,
+    dir_copy: u8,
+    volume_selector: VolumeSelector,
+    block_size_override: Option<u32>,
+) -> Option<(Vec<u32>, Vec<DirEntry>)> {
+    let key = key_for(image_path, dir_copy, volume_selector, block_size_override).ok()?;
+    let bytes = std::fs::read(cache_path(image_path)).ok()?;
+    let cached: CacheFile = bincode::deserialize(&bytes).ok()?;
+    if cached.key != key {
+        return None;
+    }
+    Some((cached.fat, cached.dir_entries))
+}
+
+/// Write `fat`/`dir_entries` to the sidecar cache for `image_path`, keyed
+/// by its current size/mtime and the request that produced them.
+pub fn save(
+    image_path: &Path,
+    dir_copy: u8,
+    volume_selector: VolumeSelector,
+    block_size_override: Option<u32>,
+    fat: &[u32],
+    dir_entries: &[DirEntry],
+) -> Result<()> {
+    let key = key_for(image_path, dir_copy, volume_selector, block_size_override)?;
+    let cache = CacheFile {
+        key,
+        fat: fat.to_vec(),
+        dir_entries: dir_entries.to_vec(),
+    };
+    let bytes = bincode::serialize(&cache).map_err(|err| NwfsError::Other(format!("failed to encode directory cache: {err}")))?;
+    let path = cache_path(image_path);
+    std::fs::write(&path, bytes).map_err(|source| NwfsError::Io { path, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_image(bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("nwfs_dircache_test_{}_{}.img", std::process::id(), bytes.len()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    /// A save followed by a load with the same image and request must
+    /// round-trip the exact FAT and directory entries that were saved.
+    #[test]
+    fn round_trips_through_save_and_load() {
+        let path = temp_image(b"hello");
+        let fat = vec![1u32, 2, 3];
+        let entries: Vec<DirEntry> = Vec::new();
+
+        save(&path, 1, VolumeSelector::Auto, None, &fat, &entries).unwrap();
+        let loaded = load(&path, 1, VolumeSelector::Auto, None);
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(cache_path(&path)).ok();
+
+        assert_eq!(loaded, Some((fat, entries)));
+    }
+
+    /// Once the image's content (and so its mtime/size) changes, the old
+    /// cache entry must no longer be served -- that's the whole point of
+    /// keying on them.
+    #[test]
+    fn a_changed_image_misses_the_cache() {
+        let path = temp_image(b"hello");
+        save(&path, 1, VolumeSelector::Auto, None, &[1, 2, 3], &[]).unwrap();
+
+        // Rewrite with different content, changing the file's length and
+        // (on most filesystems) its mtime.
+        std::fs::write(&path, b"a different, longer body").unwrap();
+
+        let loaded = load(&path, 1, VolumeSelector::Auto, None);
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(cache_path(&path)).ok();
+
+        assert_eq!(loaded, None);
+    }
+
+    /// Asking for a different `dir_copy` than the one the cache was built
+    /// for must miss, even though the image itself hasn't changed --
+    /// serving the wrong copy's entries silently would be worse than a
+    /// cache miss.
+    #[test]
+    fn a_different_dir_copy_misses_the_cache() {
+        let path = temp_image(b"hello");
+        save(&path, 1, VolumeSelector::Auto, None, &[1, 2, 3], &[]).unwrap();
+
+        let loaded = load(&path, 2, VolumeSelector::Auto, None);
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(cache_path(&path)).ok();
+
+        assert_eq!(loaded, None);
+    }
+}
@@ -0,0 +1,361 @@
+//! Directory entry types shared by both the NWFS286 and NWFS386 parsers.
+//! Each format module decodes its own on-disk layout and produces these.
+
+use std::fmt;
+
+use crate::dosdate::{DosTimestamp, TimestampFormat};
+
+/// Sentinel `parent_id` value marking a directory entry slot as available
+/// (i.e. deleted/never used), in both the NWFS286 and NWFS386 on-disk
+/// layouts. Named separately from [`crate::volume::FAT_END`] even though
+/// both happen to be `0xffff_ffff`: they mark unrelated things, and a
+/// future format revision could give either one a different value without
+/// the other needing to change.
+pub const DIRID_AVAILABLE: u32 = 0xffff_ffff;
+
+/// `dir_id` of the volume's root directory, under both NWFS286 and
+/// NWFS386: entries don't carry a separate "I am the root" flag, so
+/// callers that need to recognize the root (e.g. stopping a `parent_id`
+/// walk) compare against this instead of a bare `0`.
+pub const ROOT_DIR_ID: u32 = 0;
+
+/// DOS-compatible attribute bits, as found in NetWare directory entries.
+pub mod attr {
+    pub const READ_ONLY: u16 = 0x0001;
+    pub const HIDDEN: u16 = 0x0002;
+    pub const SYSTEM: u16 = 0x0004;
+    pub const SUBDIRECTORY: u16 = 0x0010;
+    pub const ARCHIVE: u16 = 0x0020;
+}
+
+/// NetWare trustee rights bits, in the classic `SRWCEMFA` order used by
+/// `rights`/`trustee` command output.
+pub mod rights {
+    pub const SUPERVISOR: u16 = 0x0001;
+    pub const READ: u16 = 0x0002;
+    pub const WRITE: u16 = 0x0004;
+    pub const CREATE: u16 = 0x0008;
+    pub const ERASE: u16 = 0x0010;
+    pub const MODIFY: u16 = 0x0020;
+    pub const FILE_SCAN: u16 = 0x0040;
+    pub const ACCESS_CONTROL: u16 = 0x0080;
+}
+
+/// A file or directory's attribute bits, decoded for display while still
+/// giving callers the raw value -- tooling that needs to reproduce the
+/// exact bits on extraction, or diff them against documentation, can read
+/// [`Attributes::bits`] instead of re-parsing the `Display` output. NWFS286
+/// and NWFS386 directory entries both store this as the same 16-bit word,
+/// so one type covers both formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attributes(pub u16);
+
+impl Attributes {
+    pub fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.0 & attr::SUBDIRECTORY != 0
+    }
+}
+
+impl fmt::Display for Attributes {
+    /// Formats as the conventional `RHSDA` letter form with a `-` standing
+    /// in for each bit that isn't set.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BITS: [(u16, char); 5] = [
+            (attr::READ_ONLY, 'R'),
+            (attr::HIDDEN, 'H'),
+            (attr::SYSTEM, 'S'),
+            (attr::SUBDIRECTORY, 'D'),
+            (attr::ARCHIVE, 'A'),
+        ];
+        for (bit, letter) in BITS {
+            write!(f, "{}", if self.0 & bit != 0 { letter } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
+/// A trustee's rights bitmask, displayed in the conventional `SRWCEMFA`
+/// letter form with a `-` standing in for each bit that isn't granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rights(pub u16);
+
+impl fmt::Display for Rights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BITS: [(u16, char); 8] = [
+            (rights::SUPERVISOR, 'S'),
+            (rights::READ, 'R'),
+            (rights::WRITE, 'W'),
+            (rights::CREATE, 'C'),
+            (rights::ERASE, 'E'),
+            (rights::MODIFY, 'M'),
+            (rights::FILE_SCAN, 'F'),
+            (rights::ACCESS_CONTROL, 'A'),
+        ];
+        for (bit, letter) in BITS {
+            write!(f, "{}", if self.0 & bit != 0 { letter } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
+/// A directory-access right grant, as found in a trustee list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trustee {
+    pub object_id: u32,
+    pub rights: u16,
+}
+
+impl fmt::Display for Trustee {
+    /// Formats as `<hex-id>: [SRWCEMFA]`. There's no bindery loaded here to
+    /// resolve `object_id` to an account name, so the id is shown in hex;
+    /// callers with a name map should look it up themselves and print that
+    /// instead of relying on this impl.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:08x}: [{}]", self.object_id, Rights(self.rights))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FileItem {
+    pub dir_id: u32,
+    pub parent_id: u32,
+    pub name: String,
+    pub attr: u16,
+    pub length: u32,
+    /// First block of the FAT chain holding this file's data, or
+    /// [`crate::volume::FAT_END`] for a file with no data. `0` is a normal
+    /// block address, not a sentinel -- see [`crate::volume::LogicalVolume::read_chain`].
+    pub first_block: u32,
+    pub owner_id: u16,
+    /// Id of the object that last modified this entry, distinct from
+    /// `owner_id` (the creator). Not every NetWare record carries one;
+    /// `0` means unset.
+    pub modifier_id: u16,
+    pub create_time: DosTimestamp,
+    pub modify_time: DosTimestamp,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DirectoryItem {
+    pub dir_id: u32,
+    pub parent_id: u32,
+    pub name: String,
+    pub attr: u16,
+    pub first_block: u32,
+    pub owner_id: u16,
+    /// See [`FileItem::modifier_id`].
+    pub modifier_id: u16,
+    pub create_time: DosTimestamp,
+    pub modify_time: DosTimestamp,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DirEntry {
+    File(FileItem),
+    Directory(DirectoryItem),
+}
+
+impl DirEntry {
+    pub fn name(&self) -> &str {
+        match self {
+            DirEntry::File(f) => &f.name,
+            DirEntry::Directory(d) => &d.name,
+        }
+    }
+
+    pub fn dir_id(&self) -> u32 {
+        match self {
+            DirEntry::File(f) => f.dir_id,
+            DirEntry::Directory(d) => d.dir_id,
+        }
+    }
+
+    pub fn parent_id(&self) -> u32 {
+        match self {
+            DirEntry::File(f) => f.parent_id,
+            DirEntry::Directory(d) => d.parent_id,
+        }
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        match self {
+            DirEntry::File(f) => f.deleted,
+            DirEntry::Directory(d) => d.deleted,
+        }
+    }
+
+    pub fn is_directory(&self) -> bool {
+        matches!(self, DirEntry::Directory(_))
+    }
+
+    pub fn create_time(&self) -> DosTimestamp {
+        match self {
+            DirEntry::File(f) => f.create_time,
+            DirEntry::Directory(d) => d.create_time,
+        }
+    }
+
+    pub fn modify_time(&self) -> DosTimestamp {
+        match self {
+            DirEntry::File(f) => f.modify_time,
+            DirEntry::Directory(d) => d.modify_time,
+        }
+    }
+
+    pub fn owner_id(&self) -> u16 {
+        match self {
+            DirEntry::File(f) => f.owner_id,
+            DirEntry::Directory(d) => d.owner_id,
+        }
+    }
+
+    pub fn modifier_id(&self) -> u16 {
+        match self {
+            DirEntry::File(f) => f.modifier_id,
+            DirEntry::Directory(d) => d.modifier_id,
+        }
+    }
+
+    pub fn attr(&self) -> u16 {
+        match self {
+            DirEntry::File(f) => f.attr,
+            DirEntry::Directory(d) => d.attr,
+        }
+    }
+
+    pub fn attributes(&self) -> Attributes {
+        Attributes::from_bits(self.attr())
+    }
+}
+
+/// Whether a [`DirEntryView`] represents a file or a directory, without the
+/// caller needing to match on [`DirEntry`] itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+}
+
+/// A flattened, display-ready view of a directory entry's columns, built
+/// once here so a GUI/TUI frontend can render the same listing the shell
+/// does without reimplementing the decode-and-format logic itself -- the
+/// shell's own `dir`/`ls` just formats this.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntryView {
+    pub name: String,
+    pub kind: EntryKind,
+    pub size: Option<u64>,
+    pub modified: Option<String>,
+    pub attrs: String,
+    pub owner: Option<u32>,
+}
+
+/// Build a [`DirEntryView`] of `entry`, rendering its modify time with
+/// `ts_format`. `size` is `None` for directories, which have no length of
+/// their own.
+pub fn format_entry(entry: &DirEntry, ts_format: TimestampFormat) -> DirEntryView {
+    let modify_time = entry.modify_time();
+    DirEntryView {
+        name: entry.name().to_string(),
+        kind: if entry.is_directory() { EntryKind::Directory } else { EntryKind::File },
+        size: match entry {
+            DirEntry::File(f) => Some(u64::from(f.length)),
+            DirEntry::Directory(_) => None,
+        },
+        modified: if modify_time.is_zero() {
+            None
+        } else {
+            Some(modify_time.format(ts_format))
+        },
+        attrs: entry.attributes().to_string(),
+        owner: Some(u32::from(entry.owner_id())),
+    }
+}
+
+/// A structured, typed view of one directory entry's metadata: every
+/// field a caller might want to build its own UI around, without having to
+/// parse a formatted string back apart. `trustees` is always empty for
+/// now -- this crate doesn't parse the trustee list attached to a
+/// directory entry yet, only the bit layout of a [`Trustee`] grant itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryMetadata {
+    pub name: String,
+    pub is_directory: bool,
+    pub size: Option<u64>,
+    pub attributes: Attributes,
+    pub create_time: DosTimestamp,
+    pub modify_time: DosTimestamp,
+    pub owner_id: u32,
+    pub modifier_id: u32,
+    pub first_block: u32,
+    pub deleted: bool,
+    pub trustees: Vec<Trustee>,
+}
+
+/// Build an [`EntryMetadata`] describing `entry`.
+pub fn entry_metadata(entry: &DirEntry) -> EntryMetadata {
+    EntryMetadata {
+        name: entry.name().to_string(),
+        is_directory: entry.is_directory(),
+        size: match entry {
+            DirEntry::File(f) => Some(u64::from(f.length)),
+            DirEntry::Directory(_) => None,
+        },
+        attributes: entry.attributes(),
+        create_time: entry.create_time(),
+        modify_time: entry.modify_time(),
+        owner_id: u32::from(entry.owner_id()),
+        modifier_id: u32::from(entry.modifier_id()),
+        first_block: match entry {
+            DirEntry::File(f) => f.first_block,
+            DirEntry::Directory(d) => d.first_block,
+        },
+        deleted: entry.is_deleted(),
+        trustees: Vec::new(),
+    }
+}
+
+/// Which entries a manifest export should include. Forensic recovery wants
+/// deleted entries front and center; an archivist cataloguing what's
+/// actually on the volume wants only live ones. One enum threaded through
+/// the export path serves both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeletedFilter {
+    #[default]
+    All,
+    LiveOnly,
+    DeletedOnly,
+}
+
+impl DeletedFilter {
+    pub fn matches(&self, entry: &DirEntry) -> bool {
+        match self {
+            DeletedFilter::All => true,
+            DeletedFilter::LiveOnly => !entry.is_deleted(),
+            DeletedFilter::DeletedOnly => entry.is_deleted(),
+        }
+    }
+}
+
+impl std::str::FromStr for DeletedFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "all" => Ok(Self::All),
+            "live" | "live-only" => Ok(Self::LiveOnly),
+            "deleted" | "deleted-only" => Ok(Self::DeletedOnly),
+            other => Err(format!("unknown deleted filter '{other}' (expected all, live, or deleted)")),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! DOS-style packed date/time, as stored in NetWare directory entries.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+/// Rendering choice for [`DosTimestamp::format`], so the same timestamp can
+/// be shown as the tool's own `DD-MM-YYYY HH:MM:SS` convention or as one of
+/// the two standard machine-readable forms for downstream scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampFormat {
+    /// `DD-MM-YYYY HH:MM:SS`, matching classic NetWare utility output.
+    #[default]
+    Dos,
+    /// `YYYY-MM-DDTHH:MM:SS`.
+    Iso8601,
+    /// `YYYY-MM-DDTHH:MM:SSZ`, treating the stored time as UTC.
+    Rfc3339,
+}
+
+impl std::str::FromStr for TimestampFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dos" => Ok(Self::Dos),
+            "iso8601" | "iso" => Ok(Self::Iso8601),
+            "rfc3339" => Ok(Self::Rfc3339),
+            other => Err(format!("unknown timestamp format '{other}' (expected dos, iso8601, or rfc3339)")),
+        }
+    }
+}
+
+/// A packed DOS date (`u16`) and time (`u16`) pair, the same encoding used
+/// in FAT directory entries: seconds are stored with 2-second resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct DosTimestamp {
+    pub date: u16,
+    pub time: u16,
+}
+
+impl DosTimestamp {
+    pub fn new(date: u16, time: u16) -> Self {
+        Self { date, time }
+    }
+
+    pub fn year(&self) -> u16 {
+        1980 + (self.date >> 9)
+    }
+
+    pub fn month(&self) -> u8 {
+        ((self.date >> 5) & 0x0f) as u8
+    }
+
+    pub fn day(&self) -> u8 {
+        (self.date & 0x1f) as u8
+    }
+
+    pub fn hour(&self) -> u8 {
+        (self.time >> 11) as u8
+    }
+
+    pub fn minute(&self) -> u8 {
+        ((self.time >> 5) & 0x3f) as u8
+    }
+
+    pub fn second(&self) -> u8 {
+        ((self.time & 0x1f) * 2) as u8
+    }
+
+    /// True for the all-zero timestamp NetWare uses when a field was never
+    /// set (e.g. access time on a file that was never opened).
+    pub fn is_zero(&self) -> bool {
+        self.date == 0 && self.time == 0
+    }
+
+    /// Convert to a [`std::time::SystemTime`], treating the stored fields
+    /// as UTC -- the same convention [`TimestampFormat::Rfc3339`] uses.
+    /// `None` for the all-zero sentinel or a date that doesn't exist (e.g.
+    /// a corrupt entry claiming day 31 of a 30-day month), since there's
+    /// no real instant to return in either case.
+    pub fn to_system_time(&self) -> Option<std::time::SystemTime> {
+        if self.is_zero() {
+            return None;
+        }
+        let date = NaiveDate::from_ymd_opt(i32::from(self.year()), u32::from(self.month()), u32::from(self.day()))?;
+        let naive = date.and_hms_opt(u32::from(self.hour()), u32::from(self.minute()), u32::from(self.second()))?;
+        let secs = u64::try_from(naive.and_utc().timestamp()).ok()?;
+        Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+    }
+
+    /// Render this timestamp in the given format. Always `"-"` for the
+    /// all-zero timestamp, regardless of format, since there's no real
+    /// date to express.
+    pub fn format(&self, format: TimestampFormat) -> String {
+        if self.is_zero() {
+            return "-".to_string();
+        }
+        match format {
+            TimestampFormat::Dos => format!(
+                "{:02}-{:02}-{:04} {:02}:{:02}:{:02}",
+                self.day(),
+                self.month(),
+                self.year(),
+                self.hour(),
+                self.minute(),
+                self.second()
+            ),
+            TimestampFormat::Iso8601 | TimestampFormat::Rfc3339 => {
+                let Some(date) =
+                    NaiveDate::from_ymd_opt(i32::from(self.year()), u32::from(self.month()), u32::from(self.day()))
+                else {
+                    return "-".to_string();
+                };
+                let Some(naive) =
+                    date.and_hms_opt(u32::from(self.hour()), u32::from(self.minute()), u32::from(self.second()))
+                else {
+                    return "-".to_string();
+                };
+                match format {
+                    TimestampFormat::Rfc3339 => naive.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                    _ => naive.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for DosTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(TimestampFormat::Dos))
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+/// Errors produced anywhere in the `nwfs` crate, from raw image I/O up to
+/// directory-tree traversal.
+#[derive(thiserror::Error, Debug)]
+pub enum NwfsError {
+    #[error("I/O error on {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("{operation} failed at block {block} (byte offset {offset:#x}): {source}")]
+    BlockIo {
+        operation: &'static str,
+        block: u32,
+        offset: u64,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("no MBR partition table found in {0}")]
+    NoPartitionTable(PathBuf),
+
+    #[error("partition index {index} out of range (image has {available} partition(s))")]
+    InvalidPartitionIndex { index: usize, available: usize },
+
+    #[error("partition {index} is not a NetWare partition (type 0x{partition_type:02x})")]
+    NotNetWarePartition { index: usize, partition_type: u8 },
+
+    #[error("no NetWare partition found in image")]
+    NoNetWarePartitionFound,
+
+    #[error("read of {len} byte(s) at offset {offset:#x} falls outside the valid range of this segment")]
+    BlockOutOfRange { offset: u64, len: usize },
+
+    #[error("FAT corrupt: entry at offset {offset:#x} is invalid")]
+    FatCorrupt { offset: u64 },
+
+    #[error("volume magic not recognized (expected NetWare volume header)")]
+    InvalidMagic,
+
+    #[error(
+        "this looks like an NSS/NetWare 5+ volume (signature {signature:02x?}), which is not supported by this tool's classic NWFS286/NWFS386 parsers"
+    )]
+    UnsupportedNssVolume { signature: [u8; 4] },
+
+    #[error("segments disagree on block_size: {a} vs {b}")]
+    BlockSizeMismatch { a: u32, b: u32 },
+
+    #[error(
+        "volume '{name}' reports total_blocks={expected}, but its segments cover {computed} block(s); a disk of a spanned volume may be missing, or the segment table is corrupt"
+    )]
+    VolumeBlockCountMismatch { name: String, expected: u32, computed: u32 },
+
+    #[error("volume segment table reports {num_volumes} volume(s), more than the sane maximum of {max}; the table header is likely corrupt")]
+    TooManyVolumes { num_volumes: u32, max: u32 },
+
+    #[error("volume '{name}' is missing segment(s) {missing:?} ({found} of {expected} segments present); the spanned volume can't be assembled without them")]
+    IncompleteVolumeSegments {
+        name: String,
+        expected: u32,
+        found: u32,
+        missing: Vec<u32>,
+    },
+
+    #[error("multiple distinct volumes named '{name}' found (volume_number {volume_numbers:?}); segment assembly can't tell them apart by name alone")]
+    AmbiguousVolumeName { name: String, volume_numbers: Vec<u32> },
+
+    #[error(
+        "dir_id {dir_id} is ambiguous: it names the volume root, but also the first entry of {} other director{} ({}) -- dir_id is assigned positionally within each directory's own listing, not globally, so slot 0 of any directory collides with the reserved root id; navigate to one of these by path instead",
+        paths.len(),
+        if paths.len() == 1 { "y" } else { "ies" },
+        paths.join(", ")
+    )]
+    AmbiguousDirId { dir_id: u32, paths: Vec<String> },
+
+    #[error("volume '{name}' has block_size 0 in its volume segment table; this can't be used to compute a block count or address")]
+    ZeroBlockSize { name: String },
+
+    #[error("image truncated: partition {index} needs at least {needed} byte(s) for its hotfix and volume areas, but the image only has {available}")]
+    ImageTruncated { index: usize, needed: u64, available: u64 },
+
+    #[error(
+        "image too small: need at least {needed} byte(s) to hold an MBR, but the image only has {available}; this probably isn't a disk image"
+    )]
+    ImageTooSmall { needed: u64, available: u64 },
+
+    #[error("no volume with volume_number {id} found in this partition's volume segment table")]
+    NoVolumeWithId { id: u32 },
+
+    #[error(
+        "segment order override for volume '{name}' is invalid: {order:?} must be a permutation of indices 0..{num_segments}, one per matched segment entry in on-disk table order"
+    )]
+    InvalidSegmentOrder { name: String, order: Vec<u32>, num_segments: usize },
+
+    #[error("{0}")]
+    Other(String),
+}
+
+pub type Result<T> = std::result::Result<T, NwfsError>;
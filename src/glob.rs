@@ -0,0 +1,50 @@
+//! A minimal glob matcher for a single path segment, used by the shell to
+//! expand patterns like `SYSTEM/*/` against directory entries. Only `*`
+//! (any run of characters, including none) is supported -- NetWare names
+//! are short and flat, so nothing richer has been needed yet.
+
+/// Whether `name` matches `pattern`, case-insensitively. `pattern` may
+/// contain any number of `*` wildcards; every other character must match
+/// literally.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    matches_ci(&pattern.to_ascii_uppercase(), &name.to_ascii_uppercase())
+}
+
+fn matches_ci(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((head, rest)) => {
+            let Some(after_head) = name.strip_prefix(head) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            // Try every possible split point for the remainder of the
+            // pattern, since `*` can consume any amount of `name`.
+            (0..=after_head.len()).any(|i| {
+                after_head.is_char_boundary(i) && matches_ci(rest, &after_head[i..])
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_exact_names_without_a_wildcard() {
+        assert!(matches("SYSTEM", "system"));
+        assert!(!matches("SYSTEM", "SYSTEM2"));
+    }
+
+    #[test]
+    fn star_matches_any_run_of_characters() {
+        assert!(matches("*", "anything"));
+        assert!(matches("SYS*", "SYSTEM"));
+        assert!(matches("*TEM", "SYSTEM"));
+        assert!(matches("S*M", "SYSTEM"));
+        assert!(!matches("S*X", "SYSTEM"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Top-level facade for opening one or more raw disk images and locating
+//! the NetWare partition(s) inside them.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::error::{NwfsError, Result};
+use crate::mbr::{scan_mbr, PartitionEntry};
+use crate::source::{open_source, Source};
+
+/// Smallest a NetWare partition can be and still hold its hotfix table
+/// (block 0) and the start of its volume segment table (right after it),
+/// both of which [`crate::hotfix::HotfixTable::read`] and
+/// [`crate::voltab::read_volume_table`] seek into and read from
+/// unconditionally. A partition shorter than this can't possibly be real;
+/// catching it here turns a confusing EOF deep in parsing into an
+/// immediate, actionable error.
+const MIN_NETWARE_AREA_LEN: u64 = 1024;
+
+/// How to pick a NetWare partition out of an image's MBR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartitionSelector {
+    /// Pick the first NetWare partition found, in MBR slot order.
+    #[default]
+    Auto,
+    /// Pick the MBR slot at this index (0-based), regardless of type; the
+    /// slot must actually be a NetWare partition or this is an error.
+    Index(usize),
+}
+
+/// A single image together with the partitions found in its MBR. `file`
+/// may be a plain file or a [`crate::split::SplitImage`] spanning several
+/// numbered chunks -- every format parser only ever needs [`Source`], so
+/// the distinction is invisible past this point.
+pub struct OpenImage {
+    pub path: PathBuf,
+    pub file: Box<dyn Source>,
+    pub partitions: Vec<PartitionEntry>,
+}
+
+/// A collection of image files opened as part of the same session. Most
+/// invocations only ever add one image, but `ImageList` exists so that
+/// multi-image setups (e.g. split images) are a natural extension rather
+/// than a rewrite.
+#[derive(Default)]
+pub struct ImageList {
+    images: Vec<OpenImage>,
+}
+
+impl ImageList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open `path`, read its MBR, and record any partitions found. Does not
+    /// require the image to contain a NetWare partition -- that is decided
+    /// at selection time. `path` may be the first chunk of a split image
+    /// (e.g. `image.001`), in which case its numbered siblings are opened
+    /// alongside it transparently.
+    pub fn add_image(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = open_source(&path)?;
+
+        let total_len = file.total_len().map_err(|source| NwfsError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        if total_len < 512 {
+            return Err(NwfsError::ImageTooSmall {
+                needed: 512,
+                available: total_len,
+            });
+        }
+
+        let mut sector = [0u8; 512];
+        file.read_exact(&mut sector).map_err(|source| NwfsError::Io {
+            path: path.clone(),
+            source,
+        })?;
+
+        let partitions = scan_mbr(&sector);
+        for partition in &partitions {
+            if !partition.is_netware() {
+                continue;
+            }
+            let needed = partition.byte_offset() + MIN_NETWARE_AREA_LEN;
+            if total_len < needed {
+                return Err(NwfsError::ImageTruncated {
+                    index: partition.index,
+                    needed,
+                    available: total_len,
+                });
+            }
+        }
+
+        self.images.push(OpenImage {
+            path,
+            file,
+            partitions,
+        });
+        Ok(())
+    }
+
+    /// All partitions across all added images, in image-then-slot order.
+    pub fn partitions(&self) -> impl Iterator<Item = &PartitionEntry> {
+        self.images.iter().flat_map(|img| img.partitions.iter())
+    }
+
+    /// Resolve `selector` to a concrete partition. `Auto` returns the first
+    /// NetWare partition found; `Index(n)` returns MBR slot `n` and errors
+    /// out if that slot exists but isn't a NetWare partition.
+    pub fn select_partition(&self, selector: PartitionSelector) -> Result<&PartitionEntry> {
+        match selector {
+            PartitionSelector::Auto => self
+                .partitions()
+                .find(|p| p.is_netware())
+                .ok_or(NwfsError::NoNetWarePartitionFound),
+            PartitionSelector::Index(index) => {
+                let available = self.partitions().count();
+                let partition = self
+                    .partitions()
+                    .find(|p| p.index == index)
+                    .ok_or(NwfsError::InvalidPartitionIndex { index, available })?;
+                if !partition.is_netware() {
+                    return Err(NwfsError::NotNetWarePartition {
+                        index,
+                        partition_type: partition.partition_type,
+                    });
+                }
+                Ok(partition)
+            }
+        }
+    }
+
+    pub fn images(&self) -> &[OpenImage] {
+        &self.images
+    }
+}
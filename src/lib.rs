@@ -0,0 +1,22 @@
+pub mod bytes;
+pub mod dircache;
+pub mod dirent;
+pub mod dosdate;
+pub mod error;
+pub mod glob;
+pub mod hotfix;
+pub mod image;
+pub mod mbr;
+pub mod nwfs286;
+pub mod nwfs386;
+pub mod nss;
+pub mod session;
+pub mod source;
+pub mod split;
+#[cfg(feature = "vfs")]
+pub mod vfs;
+pub mod voltab;
+pub mod volume;
+
+pub use error::{NwfsError, Result};
+pub use image::{ImageList, PartitionSelector};
@@ -0,0 +1,74 @@
+//! Minimal MBR partition table scanning, just enough to locate NetWare
+//! partitions inside a raw disk image.
+
+use crate::bytes::u32_le;
+
+/// MBR partition type byte used by NetWare 2.x/3.x ("NetWare 286").
+pub const PARTITION_TYPE_NWFS286: u8 = 0x64;
+/// MBR partition type byte used by NetWare 3.x/4.x ("NetWare 386").
+pub const PARTITION_TYPE_NWFS386: u8 = 0x65;
+
+const MBR_SIZE: usize = 512;
+const PARTITION_TABLE_OFFSET: usize = 0x1be;
+const PARTITION_ENTRY_SIZE: usize = 16;
+const PARTITION_COUNT: usize = 4;
+const MBR_SIGNATURE_OFFSET: usize = 0x1fe;
+const MBR_SIGNATURE: [u8; 2] = [0x55, 0xaa];
+
+/// A single MBR partition table entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionEntry {
+    /// Index of this entry within the MBR (0..=3).
+    pub index: usize,
+    pub partition_type: u8,
+    pub lba_start: u32,
+    pub num_sectors: u32,
+}
+
+impl PartitionEntry {
+    pub fn is_netware(&self) -> bool {
+        matches!(self.partition_type, PARTITION_TYPE_NWFS286 | PARTITION_TYPE_NWFS386)
+    }
+
+    /// Byte offset of the partition within the image.
+    pub fn byte_offset(&self) -> u64 {
+        u64::from(self.lba_start) * 512
+    }
+
+    /// Length of the partition in bytes, derived from the sector count
+    /// read alongside `lba_start` -- not discarded, since both
+    /// [`crate::image::ImageList::add_image`]'s truncation check and the
+    /// `dump-partition`/`inspect --extract-partition` carving commands
+    /// need a bounded length to know where the partition ends.
+    pub fn byte_len(&self) -> u64 {
+        u64::from(self.num_sectors) * 512
+    }
+}
+
+/// Scan a 512-byte boot sector for partition table entries. Entries with a
+/// zero type byte (unused slots) are skipped. Returns an empty vector, not
+/// an error, if the MBR signature is missing -- callers treat that as "no
+/// partition table" themselves.
+pub fn scan_mbr(sector: &[u8; MBR_SIZE]) -> Vec<PartitionEntry> {
+    if sector[MBR_SIGNATURE_OFFSET..MBR_SIGNATURE_OFFSET + 2] != MBR_SIGNATURE {
+        return Vec::new();
+    }
+
+    let mut entries = Vec::new();
+    for index in 0..PARTITION_COUNT {
+        let off = PARTITION_TABLE_OFFSET + index * PARTITION_ENTRY_SIZE;
+        let partition_type = sector[off + 4];
+        if partition_type == 0 {
+            continue;
+        }
+        let lba_start = u32_le(sector, off + 8);
+        let num_sectors = u32_le(sector, off + 12);
+        entries.push(PartitionEntry {
+            index,
+            partition_type,
+            lba_start,
+            num_sectors,
+        });
+    }
+    entries
+}
@@ -0,0 +1,74 @@
+//! Detection for Novell Storage Services (NSS) volumes -- the pooled,
+//! journaled filesystem introduced with NetWare 5, which this crate's
+//! classic NWFS286/NWFS386 parsers were never written to understand.
+//! Recognizing its signature up front turns what would otherwise be a
+//! confusing failure deep in [`crate::voltab::read_volume_table`] into a
+//! clear "not supported" message before any further parsing is attempted.
+
+use std::io::SeekFrom;
+
+use crate::error::{NwfsError, Result};
+use crate::mbr::PartitionEntry;
+use crate::source::Source;
+
+/// NSS pools begin their pool header at the same byte offset NWFS286/386
+/// use for the volume segment table, but with this 4-byte ASCII signature
+/// in place of a sane `num_volumes` count.
+const SIGNATURE_OFFSET: u64 = 512;
+const SIGNATURE: [u8; 4] = *b"NSSV";
+
+/// Probe `partition` for the NSS pool signature. Returns the raw bytes
+/// found at the signature offset when they match, so the caller can
+/// surface them in a clear error message instead of attempting to parse
+/// the pool header as a classic volume segment table.
+pub fn detect(file: &mut dyn Source, partition: &PartitionEntry) -> Result<Option<[u8; 4]>> {
+    let io_err = |source: std::io::Error| NwfsError::Io {
+        path: std::path::PathBuf::new(),
+        source,
+    };
+
+    file.seek(SeekFrom::Start(partition.byte_offset() + SIGNATURE_OFFSET))
+        .map_err(io_err)?;
+    let mut buf = [0u8; 4];
+    file.read_exact(&mut buf).map_err(io_err)?;
+    Ok(if buf == SIGNATURE { Some(buf) } else { None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn partition() -> PartitionEntry {
+        PartitionEntry {
+            index: 0,
+            partition_type: 0x65,
+            lba_start: 0,
+            num_sectors: 4096,
+        }
+    }
+
+    /// An NSS pool superblock must be recognized distinctly from a
+    /// corrupt/garbage volume segment table, so callers can report
+    /// "unsupported" instead of "corrupt" -- this is the whole point of
+    /// probing before [`crate::voltab::read_volume_table`] runs.
+    #[test]
+    fn detects_the_nss_pool_signature() {
+        let mut data = vec![0u8; (SIGNATURE_OFFSET + 4) as usize];
+        data[SIGNATURE_OFFSET as usize..].copy_from_slice(&SIGNATURE);
+        let mut cursor = Cursor::new(data);
+
+        let found = detect(&mut cursor, &partition()).unwrap();
+        assert_eq!(found, Some(SIGNATURE));
+    }
+
+    #[test]
+    fn does_not_mistake_an_ordinary_volume_table_for_an_nss_pool() {
+        let mut data = vec![0u8; (SIGNATURE_OFFSET + 4) as usize];
+        data[SIGNATURE_OFFSET as usize..].copy_from_slice(&[0x02, 0x00, 0x00, 0x00]);
+        let mut cursor = Cursor::new(data);
+
+        let found = detect(&mut cursor, &partition()).unwrap();
+        assert_eq!(found, None);
+    }
+}
@@ -0,0 +1,429 @@
+//! Parsing for NetWare 2.x/3.x ("NWFS286") volumes: a 4-byte FAT and a flat
+//! array of 128-byte directory entries.
+
+use crate::bytes::{ascii_name, u16_le, u32_le};
+use crate::dirent::{DirEntry, DirectoryItem, DIRID_AVAILABLE, FileItem};
+use crate::dosdate::DosTimestamp;
+use crate::error::{NwfsError, Result};
+use crate::source::Source;
+use crate::volume::LogicalVolume;
+
+pub const DIRECTORY_ENTRY_SIZE: usize = 128;
+const FAT_ENTRY_SIZE: usize = 4;
+
+/// Byte offset of `parent_id` within a directory entry. Named so a caller
+/// that needs to rewrite the field in place (e.g. an undelete) doesn't
+/// have to duplicate the magic number `parse_directory_entry` reads it
+/// from.
+pub(crate) const PARENT_ID_OFFSET: usize = 0x04;
+
+/// Number of directory entries packed into one block, derived from
+/// [`DIRECTORY_ENTRY_SIZE`] instead of being computed ad hoc at each call
+/// site, so a future format variant only has to change the one constant.
+fn directory_entries_per_block(block_size: u32) -> usize {
+    block_size as usize / DIRECTORY_ENTRY_SIZE
+}
+
+/// Bit 0 of the byte at offset 0x0e (`unk14`) is the real file/directory
+/// discriminator: unlike the DOS-style attribute byte, NetWare keeps it
+/// consistent even on deleted entries, where `attr`'s `SUBDIRECTORY` bit
+/// has been observed cleared.
+const UNK14_SUBDIRECTORY_BIT: u8 = 0x01;
+
+/// Read `num_entries` FAT entries starting at logical block
+/// `fat_first_block`. The FAT is simply an array of 4-byte little-endian
+/// block numbers, packed `block_size / 4` to a block.
+pub fn read_fat_table(
+    vol: &LogicalVolume,
+    file: &mut dyn Source,
+    fat_first_block: u32,
+    num_entries: u32,
+) -> Result<Vec<u32>> {
+    let (entries, warnings) = read_fat_table_lenient(vol, file, fat_first_block, num_entries, false)?;
+    debug_assert!(warnings.is_empty());
+    Ok(entries)
+}
+
+/// Like [`read_fat_table`], but with `lenient = true` a short read (e.g. an
+/// image truncated mid-table) stops the scan and returns everything parsed
+/// so far, with a warning, instead of failing the whole read.
+pub fn read_fat_table_lenient(
+    vol: &LogicalVolume,
+    file: &mut dyn Source,
+    fat_first_block: u32,
+    num_entries: u32,
+    lenient: bool,
+) -> Result<(Vec<u32>, Vec<String>)> {
+    let entries_per_block = vol.block_size as usize / FAT_ENTRY_SIZE;
+    let mut block_buf = vec![0u8; vol.block_size as usize];
+    let mut entries = Vec::with_capacity(num_entries as usize);
+    let mut warnings = Vec::new();
+
+    let mut remaining = num_entries as usize;
+    let mut block = fat_first_block;
+    while remaining > 0 {
+        if let Err(err) = vol.read_block(file, block, &mut block_buf) {
+            if lenient {
+                warnings.push(format!(
+                    "FAT table truncated at block {block} ({} of {num_entries} entries read): {err}",
+                    entries.len()
+                ));
+                break;
+            }
+            return Err(err);
+        }
+        let take = remaining.min(entries_per_block);
+        for i in 0..take {
+            entries.push(u32_le(&block_buf, i * FAT_ENTRY_SIZE));
+        }
+        remaining -= take;
+        block += 1;
+    }
+    Ok((entries, warnings))
+}
+
+/// Parse a single 128-byte directory entry. `dir_id` is the entry's own
+/// id, derived by the caller from its position in the directory table.
+pub fn parse_directory_entry(buf: &[u8], dir_id: u32) -> Result<Option<DirEntry>> {
+    if buf.len() != DIRECTORY_ENTRY_SIZE {
+        return Err(NwfsError::Other(format!(
+            "directory entry must be {DIRECTORY_ENTRY_SIZE} bytes, got {}",
+            buf.len()
+        )));
+    }
+
+    let block_or_subdir = u32_le(buf, 0x00);
+    let parent_id = u32_le(buf, PARENT_ID_OFFSET);
+    let length = u32_le(buf, 0x08);
+    let attr = u16_le(buf, 0x0c);
+    let unk14 = buf[0x0e];
+    let name_len = buf[0x0f] as usize;
+
+    if name_len == 0 {
+        // An all-zero slot: not in use.
+        return Ok(None);
+    }
+    // A `name_len` past the end of the 12-byte name field can't be
+    // trusted, but the rest of the record might still be fine -- clamp
+    // instead of dropping the whole entry.
+    let name_len = name_len.min(12);
+
+    let name = ascii_name(&buf[0x10..0x10 + name_len]);
+    let create_time = DosTimestamp::new(u16_le(buf, 0x1e), u16_le(buf, 0x1c));
+    let modify_time = DosTimestamp::new(u16_le(buf, 0x22), u16_le(buf, 0x20));
+    // A last-accessed date is not decoded: none of the remaining bytes in
+    // this entry have a verified offset for it, and NWFS286 entries carry
+    // no field we've confirmed to be one. Only `create_time`/`modify_time`
+    // are surfaced until someone can point at a sample that pins it down.
+    let owner_id = u16_le(buf, 0x26);
+    let modifier_id = u16_le(buf, 0x28);
+
+    let deleted = parent_id == DIRID_AVAILABLE;
+    let is_directory = unk14 & UNK14_SUBDIRECTORY_BIT != 0;
+
+    if is_directory {
+        Ok(Some(DirEntry::Directory(DirectoryItem {
+            dir_id,
+            parent_id,
+            name,
+            attr,
+            first_block: block_or_subdir,
+            owner_id,
+            modifier_id,
+            create_time,
+            modify_time,
+            deleted,
+        })))
+    } else {
+        Ok(Some(DirEntry::File(FileItem {
+            dir_id,
+            parent_id,
+            name,
+            attr,
+            length,
+            first_block: block_or_subdir,
+            owner_id,
+            modifier_id,
+            create_time,
+            modify_time,
+            deleted,
+        })))
+    }
+}
+
+/// Read every directory entry in the `num_blocks`-block table starting at
+/// `dir_first_block`, skipping unused slots. Like [`read_fat_table`], this
+/// walks `dir_first_block, dir_first_block + 1, ...` in order -- NWFS286
+/// has no separate block-list structure recording the directory table's
+/// blocks in some other order that would need remapping first; the table
+/// is always one fixed contiguous span, the same assumption the directory
+/// chain interleaving check in [`crate::session::Session`] relies on. Each
+/// entry's `dir_id` counts up across the whole table rather than resetting
+/// per block, matching the flat id space `parent_id` references point
+/// into.
+pub fn read_directory_entries(
+    vol: &LogicalVolume,
+    file: &mut dyn Source,
+    dir_first_block: u32,
+    num_blocks: u32,
+) -> Result<Vec<DirEntry>> {
+    let (entries, warnings) = read_directory_entries_lenient(vol, file, dir_first_block, num_blocks, false)?;
+    debug_assert!(warnings.is_empty());
+    Ok(entries)
+}
+
+/// Like [`read_directory_entries`], but with `lenient = true` a short read
+/// stops the scan and returns every entry parsed so far, with a warning,
+/// instead of failing the whole directory read.
+pub fn read_directory_entries_lenient(
+    vol: &LogicalVolume,
+    file: &mut dyn Source,
+    dir_first_block: u32,
+    num_blocks: u32,
+    lenient: bool,
+) -> Result<(Vec<DirEntry>, Vec<String>)> {
+    let entries_per_block = directory_entries_per_block(vol.block_size);
+    let mut block_buf = vec![0u8; vol.block_size as usize];
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+
+    for b in 0..num_blocks {
+        if let Err(err) = vol.read_block(file, dir_first_block + b, &mut block_buf) {
+            if lenient {
+                warnings.push(format!(
+                    "directory table truncated at block {} ({} of {num_blocks} blocks read): {err}",
+                    dir_first_block + b,
+                    b
+                ));
+                break;
+            }
+            return Err(err);
+        }
+        for slot in 0..entries_per_block {
+            let off = slot * DIRECTORY_ENTRY_SIZE;
+            let dir_id = b * entries_per_block as u32 + slot as u32;
+            if let Some(entry) = parse_directory_entry(&block_buf[off..off + DIRECTORY_ENTRY_SIZE], dir_id)? {
+                entries.push(entry);
+            }
+        }
+    }
+    Ok((entries, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tiny xorshift PRNG so the property test below doesn't need an
+    /// external crate just to generate garbage bytes.
+    struct Xorshift(u64);
+
+    impl Xorshift {
+        fn next_u8(&mut self) -> u8 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 & 0xff) as u8
+        }
+    }
+
+    /// `parse_directory_entry` must never panic, no matter how garbled the
+    /// input is -- it should just return `Ok(None)` or a real entry, since
+    /// corrupted directory blocks are exactly the case this parser exists
+    /// to survive.
+    #[test]
+    fn parse_directory_entry_never_panics_on_random_input() {
+        let mut rng = Xorshift(0x9e3779b97f4a7c15);
+        for _ in 0..10_000 {
+            let buf: Vec<u8> = (0..DIRECTORY_ENTRY_SIZE).map(|_| rng.next_u8()).collect();
+            let _ = parse_directory_entry(&buf, 0);
+        }
+    }
+
+    #[test]
+    fn parse_directory_entry_rejects_wrong_length() {
+        let buf = [0u8; DIRECTORY_ENTRY_SIZE - 1];
+        assert!(parse_directory_entry(&buf, 0).is_err());
+    }
+
+    /// A `name_len` past the end of the 12-byte name field must be clamped
+    /// to it rather than read out of bounds or cause the whole entry to be
+    /// dropped -- the rest of the record can still be trusted even if this
+    /// one byte is corrupt.
+    #[test]
+    fn parse_directory_entry_clamps_an_over_large_name_len() {
+        let mut buf = [0u8; DIRECTORY_ENTRY_SIZE];
+        buf[0x0f] = 200; // name_len, far past the 12-byte field
+        buf[0x10..0x10 + 12].copy_from_slice(b"TWELVECHARS!");
+
+        let entry = parse_directory_entry(&buf, 0).unwrap().expect("entry should still parse");
+        match entry {
+            DirEntry::File(f) => assert_eq!(f.name, "TWELVECHARS!"),
+            DirEntry::Directory(_) => panic!("expected a file entry"),
+        }
+    }
+
+    /// A FAT big enough to span more than one block must read back with
+    /// `entries[blk]` lining up with absolute block numbers, not restart
+    /// at zero for each block of the table -- on a volume with enough
+    /// blocks to need three FAT blocks, a high `first_block` in that third
+    /// block must resolve to the entry actually written there.
+    #[test]
+    fn read_fat_table_concatenates_across_multiple_fat_blocks() {
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::fs::File;
+        use std::io::Write;
+
+        let block_size = 512u32;
+        let entries_per_block = block_size / FAT_ENTRY_SIZE as u32;
+        let num_entries = entries_per_block * 2 + 3; // spans a third FAT block
+        let fat_blocks = num_entries.div_ceil(entries_per_block);
+
+        let mut image_bytes = vec![0u8; (block_size * fat_blocks) as usize];
+        // Mark the last entry (in the third FAT block) distinctly, so the
+        // test can tell it was read from the right absolute offset.
+        let last_index = num_entries - 1;
+        let last_off = last_index as usize * FAT_ENTRY_SIZE;
+        image_bytes[last_off..last_off + 4].copy_from_slice(&0xdead_beefu32.to_le_bytes());
+
+        let path = std::env::temp_dir().join(format!("nwfs286_fat_test_{}.img", std::process::id()));
+        {
+            let mut f = File::create(&path).unwrap();
+            f.write_all(&image_bytes).unwrap();
+        }
+
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: fat_blocks,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size,
+            first_block: 0,
+            num_blocks: fat_blocks,
+            image_offset: 0,
+        }];
+        let image_len = image_bytes.len() as u64;
+        let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        let fat = read_fat_table(&vol, &mut file, 0, num_entries).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(fat.len(), num_entries as usize);
+        assert_eq!(fat[last_index as usize], 0xdead_beef);
+    }
+
+    /// A directory table spanning more than one block must read those
+    /// blocks in ascending order starting at `dir_first_block` itself --
+    /// there is no stored block list to reorder first, so an entry written
+    /// to the second block must come back as the second block's entries,
+    /// not get skipped or duplicated from the first.
+    #[test]
+    fn read_directory_entries_reads_blocks_in_ascending_order() {
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::fs::File;
+        use std::io::Write;
+
+        let block_size = 512u32;
+        let dir_first_block = 5u32;
+        let num_blocks = 2u32;
+        let total_blocks = dir_first_block + num_blocks;
+
+        let mut image_bytes = vec![0u8; (block_size * total_blocks) as usize];
+        let second_block_off = (dir_first_block + 1) as usize * block_size as usize;
+        image_bytes[second_block_off + 0x0f] = 6; // name_len
+        image_bytes[second_block_off + 0x10..second_block_off + 0x16].copy_from_slice(b"SECOND");
+
+        let path = std::env::temp_dir().join(format!("nwfs286_dir_order_test_{}.img", std::process::id()));
+        {
+            let mut f = File::create(&path).unwrap();
+            f.write_all(&image_bytes).unwrap();
+        }
+
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size,
+            first_block: 0,
+            num_blocks: total_blocks,
+            image_offset: 0,
+        }];
+        let image_len = image_bytes.len() as u64;
+        let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        let entries = read_directory_entries(&vol, &mut file, dir_first_block, num_blocks).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(entries.len(), 1);
+        match &entries[0] {
+            DirEntry::File(f) => assert_eq!(f.name, "SECOND"),
+            DirEntry::Directory(_) => panic!("expected a file entry"),
+        }
+    }
+
+    /// `dir_id` keeps counting up across block boundaries rather than
+    /// resetting to 0 at the start of each block -- it has to, since
+    /// parent-id matching (e.g. [`crate::session::Session::orphans`])
+    /// treats `dir_id` as a single flat id space spanning the whole
+    /// directory table, the same space `parent_id` references point into.
+    #[test]
+    fn entry_id_numbering_stays_globally_unique_across_block_boundaries() {
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::fs::File;
+        use std::io::Write;
+
+        let block_size = 512u32;
+        let entries_per_block = directory_entries_per_block(block_size) as u32;
+        let dir_first_block = 0u32;
+        let num_blocks = 2u32;
+
+        let mut image_bytes = vec![0u8; (block_size * num_blocks) as usize];
+        let first_block_off = 0usize;
+        image_bytes[first_block_off + 0x0f] = 5; // name_len
+        image_bytes[first_block_off + 0x10..first_block_off + 0x15].copy_from_slice(b"FIRST");
+
+        let second_block_off = block_size as usize;
+        image_bytes[second_block_off + 0x0f] = 6; // name_len
+        image_bytes[second_block_off + 0x10..second_block_off + 0x16].copy_from_slice(b"SECOND");
+
+        let path = std::env::temp_dir().join(format!("nwfs286_dir_id_test_{}.img", std::process::id()));
+        {
+            let mut f = File::create(&path).unwrap();
+            f.write_all(&image_bytes).unwrap();
+        }
+
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: num_blocks,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size,
+            first_block: 0,
+            num_blocks,
+            image_offset: 0,
+        }];
+        let image_len = image_bytes.len() as u64;
+        let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        let entries = read_directory_entries(&vol, &mut file, dir_first_block, num_blocks).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(entries.len(), 2);
+        let dir_id = |e: &DirEntry| match e {
+            DirEntry::File(f) => f.dir_id,
+            DirEntry::Directory(d) => d.dir_id,
+        };
+        assert_eq!(dir_id(&entries[0]), 0);
+        assert_eq!(dir_id(&entries[1]), entries_per_block);
+    }
+}
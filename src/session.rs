@@ -0,0 +1,1933 @@
+//! Shared plumbing for opening an image, picking a partition, and loading
+//! the FAT and directory table for it -- regardless of whether the
+//! partition turns out to be NWFS286 or NWFS386. Every CLI entry point
+//! goes through this so format detection lives in exactly one place.
+
+use sha2::{Digest, Sha256};
+
+use crate::dirent::{entry_metadata, DeletedFilter, DirEntry, EntryMetadata, FileItem, ROOT_DIR_ID};
+use crate::hotfix::HotfixTable;
+use crate::image::{ImageList, PartitionSelector};
+use crate::mbr::{PartitionEntry, PARTITION_TYPE_NWFS386};
+use crate::source::{open_source, Source};
+use crate::voltab::{build_volume_lenient, read_volume_table, select_volume, VolumeSelector, INITIAL_DIR_BLOCKS};
+use crate::volume::{LogicalVolume, FAT_END};
+use crate::{nwfs286, nwfs386};
+use crate::{NwfsError, Result};
+
+/// Result of [`Session::verify_file`]: how many of a file's blocks live in
+/// a hotfix-redirected region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub total_blocks: u32,
+    pub redirected_blocks: u32,
+}
+
+/// Result of comparing two volumes block-by-block, as produced by
+/// [`Session::mirror_verify`].
+pub struct MirrorVerifyReport {
+    pub blocks_compared: u32,
+    pub first_divergent_block: Option<u32>,
+    pub mismatched_blocks: u32,
+}
+
+/// One owner's tally, as produced by [`Session::owners`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OwnerSummary {
+    pub owner_id: u16,
+    pub file_count: usize,
+    pub total_bytes: u64,
+}
+
+/// One block claimed by more than one file's FAT chain, as reported by
+/// [`Session::cross_linked_blocks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossLinkedBlock {
+    pub block: u32,
+    pub paths: Vec<String>,
+}
+
+/// Per-block allocation state as reported by [`Session::block_map`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockState {
+    /// Reachable from some live file's FAT chain, or part of a directory
+    /// table's fixed span.
+    Used,
+    /// Not referenced by anything this walk found.
+    Free,
+    /// Redirected away from by the hotfix table -- its original location
+    /// can't be trusted even if nothing chains into it anymore.
+    Bad,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Nwfs286,
+    Nwfs386,
+}
+
+impl Format {
+    pub fn detect(partition: &PartitionEntry) -> Self {
+        if partition.partition_type == PARTITION_TYPE_NWFS386 {
+            Format::Nwfs386
+        } else {
+            Format::Nwfs286
+        }
+    }
+}
+
+pub struct Session {
+    /// Path this session was opened from. For a split image, this is the
+    /// first chunk's path -- [`crate::split::SplitImage`] presents every
+    /// chunk as one contiguous [`Source`], so there's no separate "which
+    /// chunk is block N in" question to answer here.
+    pub image_path: String,
+    pub format: Format,
+    pub partition: PartitionEntry,
+    pub vol: LogicalVolume,
+    pub file: Box<dyn Source>,
+    pub fat: Vec<u32>,
+    pub dir_entries: Vec<DirEntry>,
+    pub hotfix: HotfixTable,
+    /// Non-fatal issues noticed while opening the volume (e.g. a segment
+    /// block_size disagreement tolerated because `lenient` was set).
+    pub warnings: Vec<String>,
+}
+
+/// Check that no block inside the directory table's own
+/// `[dir_first_block, dir_first_block + num_blocks)` range is also the
+/// target of a FAT chain link originating from outside that range -- i.e.
+/// that the directory table and file data don't interleave. This format
+/// allocates the directory table as a fixed contiguous span rather than
+/// threading it through the shared data FAT (there's no separate
+/// directory FAT to fall back to), so "the directory chain is pure" here
+/// means exactly that nothing else has been mistakenly chained into it; a
+/// hit is a strong signal that `dir_first_block` was computed wrong, or
+/// that the volume is corrupt, and explains symptoms like a directory that
+/// "loads partially".
+fn directory_chain_impurity(fat: &[u32], dir_first_block: u32, num_blocks: u32) -> Option<String> {
+    let dir_end = dir_first_block + num_blocks;
+    for (block, &next) in fat.iter().enumerate() {
+        let block = block as u32;
+        if (dir_first_block..dir_end).contains(&block) || next == FAT_END {
+            continue;
+        }
+        if (dir_first_block..dir_end).contains(&next) {
+            return Some(format!(
+                "file chain at block {block} points into the directory table's range ({dir_first_block}..{dir_end}); directory and file data may be interleaved"
+            ));
+        }
+    }
+    None
+}
+
+impl Session {
+    /// Open `image_path`, select a partition, auto-detect its format, and
+    /// load the FAT and root directory table for its first volume.
+    pub fn open(image_path: &str, selector: PartitionSelector) -> Result<Self> {
+        Self::open_with(image_path, selector, false)
+    }
+
+    /// Like [`Session::open`], but with `lenient = true` a segment
+    /// `block_size` disagreement is reported in `warnings` instead of
+    /// failing to open the volume at all.
+    pub fn open_with(image_path: &str, selector: PartitionSelector, lenient: bool) -> Result<Self> {
+        Self::open_with_dir_copy(image_path, selector, lenient, 1)
+    }
+
+    /// Like [`Session::open_with`], but loads directory copy `dir_copy`
+    /// (`1` for the primary copy, `2` for the mirrored copy right after
+    /// it) instead of always reading the primary. Intended for recovery
+    /// when the primary directory table is damaged.
+    pub fn open_with_dir_copy(image_path: &str, selector: PartitionSelector, lenient: bool, dir_copy: u8) -> Result<Self> {
+        Self::open_with_volume(image_path, selector, lenient, dir_copy, VolumeSelector::Auto, None, false, None)
+    }
+
+    /// Like [`Session::open_with_dir_copy`], but with an explicit
+    /// [`VolumeSelector`] instead of always taking the first volume found
+    /// in the partition's volume segment table -- for picking a volume by
+    /// `volume_number` when its name is corrupt or duplicated. `use_cache`
+    /// enables [`crate::dircache`]'s sidecar cache for the FAT and
+    /// directory table: a hit skips reading them from the image entirely,
+    /// and a miss reads them the normal way and then writes the cache for
+    /// next time. `segment_order` is the last-resort manual override
+    /// documented on [`build_volume_lenient`], for a volume whose segment
+    /// metadata is too corrupt to assemble automatically.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_with_volume(
+        image_path: &str,
+        selector: PartitionSelector,
+        lenient: bool,
+        dir_copy: u8,
+        volume_selector: VolumeSelector,
+        block_size_override: Option<u32>,
+        use_cache: bool,
+        segment_order: Option<&[u32]>,
+    ) -> Result<Self> {
+        let mut images = ImageList::new();
+        images.add_image(image_path)?;
+        let partition = *images.select_partition(selector)?;
+        let format = Format::detect(&partition);
+
+        let mut file = open_source(std::path::Path::new(image_path))?;
+        let image_len = file.total_len().map_err(|source| NwfsError::Io {
+            path: image_path.into(),
+            source,
+        })?;
+        if let Some(signature) = crate::nss::detect(&mut file, &partition)? {
+            return Err(NwfsError::UnsupportedNssVolume { signature });
+        }
+
+        let hotfix = HotfixTable::read(&mut file, &partition)?;
+        let (entries, volume_table_warnings) = read_volume_table(&mut file, &partition)?;
+        let chosen = select_volume(&entries, volume_selector)?;
+        let (vol, warnings) = build_volume_lenient(&partition, &entries, &chosen.name, image_len, lenient, segment_order)?;
+        let vol = vol.with_hotfix(hotfix.clone());
+        let vol = match block_size_override {
+            Some(block_size) => vol.with_block_size(block_size),
+            None => vol,
+        };
+        let mut warnings = warnings;
+        warnings.splice(0..0, volume_table_warnings);
+
+        let dir_first_block = match dir_copy {
+            2 => vol.dir_first_block_copy2(INITIAL_DIR_BLOCKS),
+            _ => vol.dir_first_block(),
+        };
+
+        let cached = use_cache
+            .then(|| crate::dircache::load(std::path::Path::new(image_path), dir_copy, volume_selector, block_size_override))
+            .flatten();
+        let (fat, dir_entries) = match cached {
+            Some(cached) => cached,
+            None => {
+                let (fat, dir_entries) = match format {
+                    Format::Nwfs286 => {
+                        let (fat, fat_warnings) =
+                            nwfs286::read_fat_table_lenient(&vol, &mut file, vol.fat_first_block(), vol.info.total_blocks, lenient)?;
+                        let (dir_entries, dir_warnings) =
+                            nwfs286::read_directory_entries_lenient(&vol, &mut file, dir_first_block, INITIAL_DIR_BLOCKS, lenient)?;
+                        warnings.extend(fat_warnings);
+                        warnings.extend(dir_warnings);
+                        (fat, dir_entries)
+                    }
+                    Format::Nwfs386 => (
+                        nwfs386::read_fat_table(&vol, &mut file, vol.fat_first_block(), vol.info.total_blocks)?,
+                        nwfs386::read_directory_entries(&vol, &mut file, dir_first_block, INITIAL_DIR_BLOCKS)?,
+                    ),
+                };
+                if use_cache {
+                    if let Err(err) = crate::dircache::save(
+                        std::path::Path::new(image_path),
+                        dir_copy,
+                        volume_selector,
+                        block_size_override,
+                        &fat,
+                        &dir_entries,
+                    ) {
+                        warnings.push(format!("failed to write directory cache: {err}"));
+                    }
+                }
+                (fat, dir_entries)
+            }
+        };
+
+        if let Some(warning) = directory_chain_impurity(&fat, dir_first_block, INITIAL_DIR_BLOCKS) {
+            warnings.push(warning);
+        }
+
+        Ok(Self {
+            image_path: image_path.to_string(),
+            format,
+            partition,
+            vol,
+            file,
+            fat,
+            dir_entries,
+            hotfix,
+            warnings,
+        })
+    }
+
+    pub fn read_file(&mut self, item: &FileItem) -> Result<Vec<u8>> {
+        if let Some(warning) = self.file_length_chain_mismatch(item) {
+            self.warnings.push(warning);
+        }
+        self.vol.read_chain(&mut self.file, &self.fat, item.first_block, item.length)
+    }
+
+    /// Like [`Session::read_file`], but includes the last block's slack
+    /// space (see [`LogicalVolume::read_chain_with_slack`]) and reports how
+    /// many slack bytes were appended.
+    pub fn read_file_with_slack(&mut self, item: &FileItem) -> Result<(Vec<u8>, usize)> {
+        if let Some(warning) = self.file_length_chain_mismatch(item) {
+            self.warnings.push(warning);
+        }
+        self.vol
+            .read_chain_with_slack(&mut self.file, &self.fat, item.first_block, item.length)
+    }
+
+    /// Compare `item.length` against how many blocks its FAT chain
+    /// actually holds, and describe the mismatch if the two disagree by
+    /// more than the at-most-one-block slack a partially-filled last
+    /// block accounts for. A length far beyond what the chain can hold,
+    /// or a chain far longer than the length calls for, both point at
+    /// corruption (or a sparse/compressed file this crate doesn't yet
+    /// understand) -- this is a warning, not an error, because
+    /// [`LogicalVolume::read_chain`] already stops once it has read
+    /// `length` bytes and so isn't itself at risk from a too-long chain.
+    /// Returns `None` if the chain can't be walked at all (e.g. a corrupt
+    /// FAT index); [`Session::read_file`]'s own chain walk will surface
+    /// that as a proper error.
+    fn file_length_chain_mismatch(&self, item: &FileItem) -> Option<String> {
+        let block_size = self.vol.block_size;
+        let expected_blocks = item.length.div_ceil(block_size).max(1);
+
+        let mut actual_blocks = 0u32;
+        let mut block = item.first_block;
+        let mut seen = std::collections::HashSet::new();
+        loop {
+            if !seen.insert(block) {
+                break; // cyclic chain; treat what we've counted so far as final
+            }
+            actual_blocks += 1;
+            let next = *self.fat.get(block as usize)?;
+            if next == FAT_END || next == block {
+                break;
+            }
+            block = next;
+        }
+
+        if actual_blocks != expected_blocks {
+            Some(format!(
+                "'{}' declares length {} ({} block(s) at block_size {block_size}), but its FAT chain has {} block(s)",
+                item.name, item.length, expected_blocks, actual_blocks
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Walk `item`'s FAT chain without reading any data, and report how
+    /// many of its blocks fall in hotfix-redirected regions -- blocks that
+    /// were remapped away from a detected bad sector, and so can't be
+    /// trusted to read cleanly from their original location.
+    pub fn verify_file(&self, item: &FileItem) -> Result<VerifyReport> {
+        let mut total_blocks = 0u32;
+        let mut redirected_blocks = 0u32;
+        let mut block = item.first_block;
+        let mut seen = std::collections::HashSet::new();
+        loop {
+            if !seen.insert(block) {
+                break; // cyclic chain; stop rather than loop forever
+            }
+            total_blocks += 1;
+            if self.hotfix.is_redirected(block) {
+                redirected_blocks += 1;
+            }
+            let next = *self.fat.get(block as usize).ok_or(NwfsError::FatCorrupt {
+                offset: u64::from(block) * 4,
+            })?;
+            if next == FAT_END || next == block {
+                break;
+            }
+            block = next;
+        }
+        Ok(VerifyReport {
+            total_blocks,
+            redirected_blocks,
+        })
+    }
+
+    /// Which segment(s) of the volume `item`'s blocks physically live on,
+    /// in the order first encountered walking its FAT chain, deduplicated.
+    /// For a spanned volume this is the triage a reader wants after losing
+    /// one physical disk of the set: a file whose chain returns a single
+    /// segment number survives as long as that one disk is intact, while
+    /// one spanning several segments is only as safe as the least healthy
+    /// of them.
+    pub fn file_segments(&self, item: &FileItem) -> Result<Vec<u32>> {
+        let mut segments = Vec::new();
+        let mut block = item.first_block;
+        let mut seen = std::collections::HashSet::new();
+        loop {
+            if !seen.insert(block) {
+                break; // cyclic chain; stop rather than loop forever
+            }
+            let segment_num = self.vol.segment_for_block(block)?.segment_num;
+            if !segments.contains(&segment_num) {
+                segments.push(segment_num);
+            }
+            let next = *self.fat.get(block as usize).ok_or(NwfsError::FatCorrupt {
+                offset: u64::from(block) * 4,
+            })?;
+            if next == FAT_END || next == block {
+                break;
+            }
+            block = next;
+        }
+        Ok(segments)
+    }
+
+    /// Compare `self` and `other` block-by-block over their shared range,
+    /// for confirming whether two images of the same duplexed volume were
+    /// actually in sync at imaging time. Blocks are read raw via
+    /// `seek_block`/`read_block`, bypassing the FAT and directory
+    /// entirely, since a mirror divergence in unused space is still a
+    /// divergence worth knowing about. If the two volumes disagree on
+    /// `block_size` or `total_blocks`, only the smaller of each is used,
+    /// since that's the largest range both images can actually answer for.
+    pub fn mirror_verify(&mut self, other: &mut Session) -> Result<MirrorVerifyReport> {
+        let block_size = self.vol.block_size.min(other.vol.block_size) as usize;
+        let total_blocks = self.vol.info.total_blocks.min(other.vol.info.total_blocks);
+        let mut buf_a = vec![0u8; block_size];
+        let mut buf_b = vec![0u8; block_size];
+        let mut first_divergent_block = None;
+        let mut mismatched_blocks = 0u32;
+        for block in 0..total_blocks {
+            self.vol.read_block(&mut self.file, block, &mut buf_a)?;
+            other.vol.read_block(&mut other.file, block, &mut buf_b)?;
+            if buf_a != buf_b {
+                mismatched_blocks += 1;
+                first_divergent_block.get_or_insert(block);
+            }
+        }
+        Ok(MirrorVerifyReport {
+            blocks_compared: total_blocks,
+            first_divergent_block,
+            mismatched_blocks,
+        })
+    }
+
+    /// Read the entries of the directory table starting at `first_block`,
+    /// without recursing into any of its own subdirectories. Used to
+    /// export a single directory's files rather than always the root.
+    pub fn read_directory_at(&mut self, first_block: u32) -> Result<Vec<DirEntry>> {
+        match self.format {
+            Format::Nwfs286 => {
+                crate::nwfs286::read_directory_entries(&self.vol, &mut self.file, first_block, INITIAL_DIR_BLOCKS)
+            }
+            Format::Nwfs386 => {
+                crate::nwfs386::read_directory_entries(&self.vol, &mut self.file, first_block, INITIAL_DIR_BLOCKS)
+            }
+        }
+    }
+
+    pub fn find_file(&self, name: &str) -> Result<FileItem> {
+        self.dir_entries
+            .iter()
+            .find_map(|e| match e {
+                DirEntry::File(f) if f.name == name => Some(f.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| NwfsError::Other(format!("'{name}' not found")))
+    }
+
+    /// Look up a file by its parent directory id and name. Unlike
+    /// [`Session::find_file`], which matches on name alone, this
+    /// disambiguates between files that happen to share a name in
+    /// different directories -- the building block for reading a file
+    /// programmatically without going through the interactive shell.
+    pub fn find_file_in(&self, parent_id: u32, name: &str) -> Result<FileItem> {
+        self.dir_entries
+            .iter()
+            .find_map(|e| match e {
+                DirEntry::File(f) if f.parent_id == parent_id && f.name == name => Some(f.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| NwfsError::Other(format!("'{name}' not found in directory {parent_id}")))
+    }
+
+    /// Read a file's data given its parent directory id and name, in one
+    /// call -- the programmatic equivalent of the shell's `cat`/`get`.
+    pub fn read_by_id_and_name(&mut self, parent_id: u32, name: &str) -> Result<Vec<u8>> {
+        let item = self.find_file_in(parent_id, name)?;
+        self.read_file(&item)
+    }
+
+    /// Look up a file or directory by its parent id and name and return its
+    /// metadata as a typed [`EntryMetadata`], for callers that want
+    /// structured fields instead of a formatted string.
+    pub fn metadata_in(&self, parent_id: u32, name: &str) -> Result<EntryMetadata> {
+        self.dir_entries
+            .iter()
+            .find(|e| e.parent_id() == parent_id && e.name() == name)
+            .map(entry_metadata)
+            .ok_or_else(|| NwfsError::Other(format!("'{name}' not found in directory {parent_id}")))
+    }
+
+    /// Like [`Session::metadata_in`], but matches on name alone, against
+    /// the directory currently loaded into [`Session::dir_entries`].
+    pub fn metadata(&self, name: &str) -> Result<EntryMetadata> {
+        self.dir_entries
+            .iter()
+            .find(|e| e.name() == name)
+            .map(entry_metadata)
+            .ok_or_else(|| NwfsError::Other(format!("'{name}' not found")))
+    }
+
+    /// Search every directory entry reachable from the root, at any depth,
+    /// for a name containing `substring` (case-insensitive), returning each
+    /// match's full path alongside the entry itself. A depth limit guards
+    /// against a corrupt directory chain that loops back on an ancestor.
+    pub fn find_substring(&mut self, substring: &str) -> Result<Vec<(String, DirEntry)>> {
+        const MAX_DEPTH: u32 = 64;
+        let mut out = Vec::new();
+        self.find_substring_rec(self.vol.dir_first_block(), "", &substring.to_lowercase(), 0, MAX_DEPTH, &mut out)?;
+        Ok(out)
+    }
+
+    fn find_substring_rec(
+        &mut self,
+        first_block: u32,
+        prefix: &str,
+        needle_lower: &str,
+        depth: u32,
+        max_depth: u32,
+        out: &mut Vec<(String, DirEntry)>,
+    ) -> Result<()> {
+        if depth >= max_depth {
+            return Ok(());
+        }
+        let entries = self.read_directory_at(first_block)?;
+        for e in entries {
+            let path = if prefix.is_empty() {
+                e.name().to_string()
+            } else {
+                format!("{prefix}/{}", e.name())
+            };
+            if e.name().to_lowercase().contains(needle_lower) {
+                out.push((path.clone(), e.clone()));
+            }
+            if let DirEntry::Directory(d) = &e {
+                self.find_substring_rec(d.first_block, &path, needle_lower, depth + 1, max_depth, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deterministic fingerprint of the volume's logical contents: every
+    /// file's full path and data, hashed in a fixed (path-sorted) order so
+    /// two captures of the same disk yield identical digests regardless of
+    /// how their blocks happen to be laid out. A depth limit guards against
+    /// a corrupt directory chain the same way [`Session::find_substring`]
+    /// does.
+    pub fn content_fingerprint(&mut self) -> Result<[u8; 32]> {
+        let files = self.file_tree()?;
+
+        let mut hasher = Sha256::new();
+        for (path, item) in files {
+            let data = self.read_file(&item)?;
+            hasher.update((path.len() as u64).to_le_bytes());
+            hasher.update(path.as_bytes());
+            hasher.update((data.len() as u64).to_le_bytes());
+            hasher.update(&data);
+        }
+        Ok(hasher.finalize().into())
+    }
+
+    /// Every file reachable from the root, as `(full_path, FileItem)`
+    /// pairs sorted by path -- the same path-sorted walk
+    /// [`Session::content_fingerprint`] hashes, exposed on its own so
+    /// callers that need to compare two volumes file-by-file (e.g. a
+    /// `diff` command) don't have to re-hash the whole volume to get a
+    /// stable path order. A depth limit guards against a corrupt
+    /// directory chain the same way [`Session::find_substring`] does.
+    pub fn file_tree(&mut self) -> Result<Vec<(String, FileItem)>> {
+        const MAX_DEPTH: u32 = 64;
+        let mut files = Vec::new();
+        self.collect_files_rec(self.vol.dir_first_block(), "", 0, MAX_DEPTH, &mut files)?;
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(files)
+    }
+
+    /// SHA-256 of one file's data, for a caller that wants to know whether
+    /// two files with the same path and length across two volumes actually
+    /// differ in content.
+    pub fn file_fingerprint(&mut self, item: &FileItem) -> Result<[u8; 32]> {
+        let data = self.read_file(item)?;
+        let mut hasher = Sha256::new();
+        hasher.update(&data);
+        Ok(hasher.finalize().into())
+    }
+
+    /// Find a deleted file named `name` (case-insensitive, exact match)
+    /// anywhere under the root, for [`Session::undelete`]. Returns the
+    /// `first_block` of the directory table the entry lives in alongside
+    /// the entry itself, since that -- combined with `item.dir_id` -- is
+    /// what pins down the entry's byte offset on disk. Errors if no match
+    /// is found, or if more than one deleted file shares the name (the
+    /// caller would have no way to tell them apart).
+    pub fn find_deleted_file(&mut self, name: &str) -> Result<(u32, FileItem)> {
+        const MAX_DEPTH: u32 = 64;
+        let mut out = Vec::new();
+        self.find_deleted_file_rec(self.vol.dir_first_block(), &name.to_lowercase(), 0, MAX_DEPTH, &mut out)?;
+        match out.len() {
+            0 => Err(NwfsError::Other(format!("no deleted file named '{name}' found"))),
+            1 => Ok(out.remove(0)),
+            n => Err(NwfsError::Other(format!(
+                "{n} deleted files named '{name}' found; disambiguate by dir_id"
+            ))),
+        }
+    }
+
+    fn find_deleted_file_rec(
+        &mut self,
+        first_block: u32,
+        name_lower: &str,
+        depth: u32,
+        max_depth: u32,
+        out: &mut Vec<(u32, FileItem)>,
+    ) -> Result<()> {
+        if depth >= max_depth {
+            return Ok(());
+        }
+        let entries = self.read_directory_at(first_block)?;
+        for e in entries {
+            match e {
+                DirEntry::File(f) if f.deleted && f.name.to_lowercase() == name_lower => {
+                    out.push((first_block, f));
+                }
+                DirEntry::Directory(d) => {
+                    self.find_deleted_file_rec(d.first_block, name_lower, depth + 1, max_depth, out)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Restore a deleted file by clearing the `parent_id` field that marks
+    /// it deleted, re-parenting it under `into_dir_id`.
+    ///
+    /// This format has no separate `delete_time`/`delete_id` fields to
+    /// clear -- `parent_id == DIRID_AVAILABLE` *is* the deletion marker on
+    /// both NWFS286 and NWFS386 -- which means the entry's original parent
+    /// is gone the moment it's deleted, not recoverable from this entry
+    /// alone. `into_dir_id` is where the caller wants the file to reappear;
+    /// there is no way to infer "where it used to be".
+    ///
+    /// Refuses to touch the image unless the file's FAT chain still reads
+    /// back cleanly (deleted space is the first thing NetWare reallocates,
+    /// so a chain that no longer reads is a strong sign the blocks have
+    /// already been overwritten by something else). Writes directly to
+    /// `self.image_path` with a fresh read-write file handle, bypassing
+    /// [`Source`] entirely -- every other operation in this crate is
+    /// read-only, so `Source` has no write half, and a split image isn't
+    /// supported here: this opens `image_path` itself, not whichever
+    /// numbered chunk the offset actually falls in.
+    pub fn undelete(&mut self, name: &str, into_dir_id: u32) -> Result<FileItem> {
+        let (containing_first_block, item) = self.find_deleted_file(name)?;
+        self.read_file(&item)?;
+
+        let offset = self.parent_id_offset(containing_first_block, item.dir_id)?;
+        use std::io::{Seek, Write};
+        let mut f = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&self.image_path)
+            .map_err(|source| NwfsError::Io {
+                path: self.image_path.clone().into(),
+                source,
+            })?;
+        f.seek(std::io::SeekFrom::Start(offset)).map_err(|source| NwfsError::Io {
+            path: self.image_path.clone().into(),
+            source,
+        })?;
+        f.write_all(&into_dir_id.to_le_bytes()).map_err(|source| NwfsError::Io {
+            path: self.image_path.clone().into(),
+            source,
+        })?;
+
+        let mut restored = item;
+        restored.parent_id = into_dir_id;
+        restored.deleted = false;
+        Ok(restored)
+    }
+
+    /// Absolute byte offset of `dir_id`'s `parent_id` field, given the
+    /// `first_block` of the directory table it's stored in. `dir_id` is
+    /// assigned sequentially (`block_index * entries_per_block + slot`)
+    /// by both [`nwfs286::read_directory_entries`] and
+    /// [`nwfs386::read_directory_entries`], so it can be inverted back
+    /// into a block and a slot within that block.
+    fn parent_id_offset(&self, dir_table_first_block: u32, dir_id: u32) -> Result<u64> {
+        let (entry_size, parent_id_off) = match self.format {
+            Format::Nwfs286 => (nwfs286::DIRECTORY_ENTRY_SIZE, nwfs286::PARENT_ID_OFFSET),
+            Format::Nwfs386 => (nwfs386::DIRECTORY_ENTRY_SIZE, nwfs386::PARENT_ID_OFFSET),
+        };
+        let entries_per_block = self.vol.block_size as usize / entry_size;
+        let block = dir_table_first_block + dir_id / entries_per_block as u32;
+        let slot = dir_id as usize % entries_per_block;
+        let block_offset = self.vol.block_to_offset(block)?;
+        Ok(block_offset + (slot * entry_size + parent_id_off) as u64)
+    }
+
+    fn collect_files_rec(
+        &mut self,
+        first_block: u32,
+        prefix: &str,
+        depth: u32,
+        max_depth: u32,
+        out: &mut Vec<(String, FileItem)>,
+    ) -> Result<()> {
+        if depth >= max_depth {
+            return Ok(());
+        }
+        let entries = self.read_directory_at(first_block)?;
+        for e in entries {
+            let path = if prefix.is_empty() {
+                e.name().to_string()
+            } else {
+                format!("{prefix}/{}", e.name())
+            };
+            match e {
+                DirEntry::File(f) => out.push((path, f)),
+                DirEntry::Directory(d) => self.collect_files_rec(d.first_block, &path, depth + 1, max_depth, out)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Walk every directory entry reachable from the root, at any depth,
+    /// for a manifest export: each entry's full path alongside the entry
+    /// itself, restricted to those matching `filter`. A depth limit guards
+    /// against a corrupt directory chain the same way
+    /// [`Session::find_substring`] does.
+    pub fn manifest(&mut self, filter: DeletedFilter) -> Result<Vec<(String, DirEntry)>> {
+        const MAX_DEPTH: u32 = 64;
+        let mut out = Vec::new();
+        self.manifest_rec(self.vol.dir_first_block(), "", filter, 0, MAX_DEPTH, &mut out)?;
+        Ok(out)
+    }
+
+    fn manifest_rec(
+        &mut self,
+        first_block: u32,
+        prefix: &str,
+        filter: DeletedFilter,
+        depth: u32,
+        max_depth: u32,
+        out: &mut Vec<(String, DirEntry)>,
+    ) -> Result<()> {
+        if depth >= max_depth {
+            return Ok(());
+        }
+        let entries = self.read_directory_at(first_block)?;
+        for e in entries {
+            let path = if prefix.is_empty() {
+                e.name().to_string()
+            } else {
+                format!("{prefix}/{}", e.name())
+            };
+            if filter.matches(&e) {
+                out.push((path.clone(), e.clone()));
+            }
+            if let DirEntry::Directory(d) = &e {
+                self.manifest_rec(d.first_block, &path, filter, depth + 1, max_depth, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every entry reachable from the root (including deleted ones) whose
+    /// `parent_id` doesn't match the `dir_id` of any directory found during
+    /// the same walk, grouped by that missing parent id. The entry was
+    /// still found by descending into some live directory's own block
+    /// chain -- what's wrong is the `parent_id` field *recorded on the
+    /// entry itself*, which should echo that directory's `dir_id` back but
+    /// doesn't, typically because the slot that used to hold the real
+    /// parent has since been overwritten by something with a different id.
+    ///
+    /// This can't find a subtree whose *linking* directory entry was
+    /// itself completely overwritten rather than merely marked deleted --
+    /// that subtree's block chain is no longer referenced from anywhere
+    /// this walk can reach, and finding it would mean scanning every block
+    /// on disk for directory-shaped content, which this crate doesn't do.
+    #[allow(clippy::type_complexity)]
+    pub fn orphans(&mut self) -> Result<Vec<(u32, Vec<(String, DirEntry)>)>> {
+        let all = self.manifest(DeletedFilter::All)?;
+        let known_dirs: std::collections::HashSet<u32> = all
+            .iter()
+            .filter_map(|(_, e)| match e {
+                DirEntry::Directory(d) => Some(d.dir_id),
+                _ => None,
+            })
+            .collect();
+
+        let mut groups: std::collections::BTreeMap<u32, Vec<(String, DirEntry)>> = std::collections::BTreeMap::new();
+        for (path, e) in all {
+            let parent_id = e.parent_id();
+            if parent_id != ROOT_DIR_ID && !known_dirs.contains(&parent_id) {
+                groups.entry(parent_id).or_default().push((path, e));
+            }
+        }
+        Ok(groups.into_iter().collect())
+    }
+
+    /// Classify every block on the volume as [`BlockState::Used`] (part of a
+    /// live file's FAT chain, or of a directory table's fixed span),
+    /// [`BlockState::Free`] (not referenced by anything this walk found), or
+    /// [`BlockState::Bad`] (redirected away from by the hotfix table). This
+    /// crate has no separate free-space bitmap to read -- NetWare derives
+    /// free space the same way, by elimination -- so "free" here means
+    /// exactly "not found while walking every live file and directory",
+    /// same caveat as [`Session::orphans`] about subtrees this crate can't
+    /// reach at all.
+    pub fn block_map(&mut self) -> Result<Vec<BlockState>> {
+        let total = self.vol.info.total_blocks as usize;
+        let mut states = vec![BlockState::Free; total];
+
+        for entry in self.hotfix.entries() {
+            if let Some(state) = states.get_mut(entry.original_block as usize) {
+                *state = BlockState::Bad;
+            }
+        }
+
+        let mark_span = |first_block: u32, num_blocks: u32, states: &mut [BlockState]| {
+            for b in first_block..first_block + num_blocks {
+                if let Some(state) = states.get_mut(b as usize) {
+                    if *state != BlockState::Bad {
+                        *state = BlockState::Used;
+                    }
+                }
+            }
+        };
+        mark_span(self.vol.dir_first_block(), INITIAL_DIR_BLOCKS, &mut states);
+
+        let live = self.manifest(DeletedFilter::LiveOnly)?;
+        for (_, e) in live {
+            match e {
+                DirEntry::Directory(d) => mark_span(d.first_block, INITIAL_DIR_BLOCKS, &mut states),
+                DirEntry::File(f) => {
+                    let mut block = f.first_block;
+                    let mut seen = std::collections::HashSet::new();
+                    loop {
+                        if !seen.insert(block) {
+                            break; // cyclic chain; stop rather than loop forever
+                        }
+                        if let Some(state) = states.get_mut(block as usize) {
+                            if *state != BlockState::Bad {
+                                *state = BlockState::Used;
+                            }
+                        }
+                        let next = match self.fat.get(block as usize) {
+                            Some(&n) => n,
+                            None => break,
+                        };
+                        if next == FAT_END || next == block {
+                            break;
+                        }
+                        block = next;
+                    }
+                }
+            }
+        }
+        Ok(states)
+    }
+
+    /// Find blocks claimed by more than one file's FAT chain -- a classic
+    /// corruption symptom (cross-linking) that usually means the directory
+    /// table or FAT itself is corrupt, and that every file named in the
+    /// result is suspect, not just one of them. Builds a block-ownership
+    /// map while walking the chain of every file [`Session::file_tree`]
+    /// returns, deleted files included (unlike [`Session::block_map`], which
+    /// walks live files only), keeping track of *which* file(s) claimed
+    /// each block instead of collapsing to a single `Used` state.
+    /// An empty file's `first_block` is [`FAT_END`], not a real block (see
+    /// [`crate::dirent::FileItem::first_block`]), so it's skipped rather
+    /// than recorded as an owner of block `0xffff_ffff` -- otherwise any
+    /// two empty files would falsely "cross-link".
+    pub fn cross_linked_blocks(&mut self) -> Result<Vec<CrossLinkedBlock>> {
+        let mut owners: std::collections::HashMap<u32, Vec<String>> = std::collections::HashMap::new();
+
+        for (path, item) in self.file_tree()? {
+            if item.first_block == FAT_END {
+                continue; // empty file, no blocks to own
+            }
+            let mut block = item.first_block;
+            let mut seen = std::collections::HashSet::new();
+            loop {
+                if !seen.insert(block) {
+                    break; // cyclic chain; stop rather than loop forever
+                }
+                owners.entry(block).or_default().push(path.clone());
+                let next = match self.fat.get(block as usize) {
+                    Some(&n) => n,
+                    None => break,
+                };
+                if next == FAT_END || next == block {
+                    break;
+                }
+                block = next;
+            }
+        }
+
+        let mut cross_linked: Vec<CrossLinkedBlock> = owners
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(block, paths)| CrossLinkedBlock { block, paths })
+            .collect();
+        cross_linked.sort_by_key(|c| c.block);
+        Ok(cross_linked)
+    }
+
+    /// Per-owner file count and total byte total under `dir_id` (the
+    /// volume root when `None`), recursively -- `du`, grouped by
+    /// `owner_id` instead of by subdirectory -- sorted by total bytes
+    /// descending. There's no bindery loaded here to resolve an `owner_id`
+    /// to an account name (same caveat as [`crate::dirent::Trustee`]'s
+    /// `Display` impl), so this reports raw ids; a caller with a name map
+    /// should look them up itself. Reuses [`Session::collect_files_rec`],
+    /// the same recursive walk [`Session::file_tree`] does, so deleted
+    /// files are counted too -- filter the result yourself if that's not
+    /// wanted.
+    pub fn owners(&mut self, dir_id: Option<u32>) -> Result<Vec<OwnerSummary>> {
+        const MAX_DEPTH: u32 = 64;
+        let first_block = self.first_block_of_dir_or_root(dir_id)?;
+        let mut files = Vec::new();
+        self.collect_files_rec(first_block, "", 0, MAX_DEPTH, &mut files)?;
+
+        let mut tally: std::collections::HashMap<u16, (usize, u64)> = std::collections::HashMap::new();
+        for (_, f) in &files {
+            let entry = tally.entry(f.owner_id).or_default();
+            entry.0 += 1;
+            entry.1 += u64::from(f.length);
+        }
+
+        let mut summaries: Vec<OwnerSummary> = tally
+            .into_iter()
+            .map(|(owner_id, (file_count, total_bytes))| OwnerSummary {
+                owner_id,
+                file_count,
+                total_bytes,
+            })
+            .collect();
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.total_bytes));
+        Ok(summaries)
+    }
+
+    /// Per-immediate-subdirectory size totals under `dir_id` (the volume
+    /// root when `None`), sorted descending, alongside the grand total for
+    /// `dir_id` itself -- `du -d1`, in spirit. By default sums `length`
+    /// fields, which is cheap and matches what extraction will actually
+    /// write; pass `allocated` to instead sum each file's FAT chain in full
+    /// blocks (via [`Session::verify_file`]), which better reflects disk
+    /// usage for heavily fragmented or slack-padded files but is far
+    /// slower on a large volume since it walks every chain.
+    pub fn du(&mut self, dir_id: Option<u32>, allocated: bool) -> Result<(Vec<(String, u64)>, u64)> {
+        const MAX_DEPTH: u32 = 64;
+        let first_block = self.first_block_of_dir_or_root(dir_id)?;
+        let entries = self.read_directory_at(first_block)?;
+
+        let mut per_dir = Vec::new();
+        let mut grand_total = 0u64;
+        for e in entries {
+            match e {
+                DirEntry::File(f) => grand_total += self.file_size(&f, allocated)?,
+                DirEntry::Directory(d) => {
+                    let size = self.subtree_size(d.first_block, allocated, 1, MAX_DEPTH)?;
+                    grand_total += size;
+                    per_dir.push((d.name, size));
+                }
+            }
+        }
+        per_dir.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+        Ok((per_dir, grand_total))
+    }
+
+    fn subtree_size(&mut self, first_block: u32, allocated: bool, depth: u32, max_depth: u32) -> Result<u64> {
+        if depth >= max_depth {
+            return Ok(0);
+        }
+        let entries = self.read_directory_at(first_block)?;
+        let mut total = 0u64;
+        for e in entries {
+            match e {
+                DirEntry::File(f) => total += self.file_size(&f, allocated)?,
+                DirEntry::Directory(d) => total += self.subtree_size(d.first_block, allocated, depth + 1, max_depth)?,
+            }
+        }
+        Ok(total)
+    }
+
+    fn file_size(&self, item: &FileItem, allocated: bool) -> Result<u64> {
+        if !allocated {
+            return Ok(u64::from(item.length));
+        }
+        let report = self.verify_file(item)?;
+        Ok(u64::from(report.total_blocks) * u64::from(self.vol.block_size))
+    }
+
+    /// Expand a `/`-separated path pattern (each segment matched with
+    /// [`crate::glob`], so e.g. `SYSTEM/*` or `*` are valid) against the
+    /// directory tree starting at the root, returning every matched
+    /// directory's path (relative to the root, empty string for the root
+    /// itself) alongside its contents. Unlike [`Session::find_substring`],
+    /// this only descends along segments that are actually present in the
+    /// pattern, so it stays cheap even on a large tree.
+    ///
+    /// Only live directories are considered at each segment: a deleted
+    /// directory entry never matches, even when its name collides with a
+    /// live one (NetWare doesn't reclaim a dir id on delete, so both can be
+    /// present in the same block) -- descending into it would walk a stale
+    /// subtree that may no longer be linked from anywhere else.
+    pub fn list_matching_dirs(&mut self, pattern: &str) -> Result<Vec<(String, Vec<DirEntry>)>> {
+        let segments: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+
+        let mut candidates = vec![(String::new(), self.vol.dir_first_block())];
+        for seg in &segments {
+            let mut next = Vec::new();
+            for (path, first_block) in &candidates {
+                for e in self.read_directory_at(*first_block)? {
+                    if let DirEntry::Directory(d) = &e {
+                        if !d.deleted && crate::glob::matches(seg, &d.name) {
+                            let child_path = if path.is_empty() { d.name.clone() } else { format!("{path}/{}", d.name) };
+                            next.push((child_path, d.first_block));
+                        }
+                    }
+                }
+            }
+            candidates = next;
+        }
+
+        let mut out = Vec::with_capacity(candidates.len());
+        for (path, first_block) in candidates {
+            let entries = self.read_directory_at(first_block)?;
+            out.push((path, entries));
+        }
+        Ok(out)
+    }
+
+    /// Well-known NetWare system log filenames, for [`Session::syslogs`].
+    /// These are by name only -- the directory they live in varies across
+    /// server configurations (`SYS:SYSTEM`, `SYS:_NETWARE`, or the volume
+    /// root depending on version and how the admin set things up), so this
+    /// isn't a fixed path lookup the way [`Session::find_file_in`] is.
+    pub const KNOWN_SYSTEM_LOG_NAMES: &[&str] = &["VOL$LOG.ERR", "TTS$LOG.ERR", "SYS$LOG.ERR", "ABEND.LOG"];
+
+    /// Locate every file anywhere in the volume whose name matches one of
+    /// [`Session::KNOWN_SYSTEM_LOG_NAMES`], for a first look at what a
+    /// server logged before it went down. A thin wrapper over
+    /// [`Session::find_substring`]; it encodes which filenames are worth
+    /// looking for, not where NetWare necessarily put them.
+    pub fn syslogs(&mut self) -> Result<Vec<(String, FileItem)>> {
+        let mut out = Vec::new();
+        for name in Self::KNOWN_SYSTEM_LOG_NAMES {
+            for (path, entry) in self.find_substring(name)? {
+                if let DirEntry::File(f) = entry {
+                    if f.name.eq_ignore_ascii_case(name) {
+                        out.push((path, f));
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Recover a deleted directory's former contents by its old `dir_id`.
+    ///
+    /// Deletion clears a directory entry's own slot but does not touch the
+    /// children that used to live under it: their `parent_id` still points
+    /// at the deleted directory's `dir_id`, and its directory block range
+    /// is untouched until something else claims those blocks. This walks
+    /// the whole tree (deleted and live entries alike, since a deleted
+    /// directory can itself be nested under another deleted one) looking
+    /// for a [`DirectoryItem`] with a matching `dir_id`, then reads its
+    /// directory blocks directly by `first_block` the same way a live
+    /// directory would be read.
+    pub fn salvage_directory(&mut self, dir_id: u32) -> Result<Vec<DirEntry>> {
+        let first_block = self.first_block_of_dir(dir_id)?;
+        self.read_directory_at(first_block)
+    }
+
+    /// Change the directory currently loaded into [`Session::dir_entries`]
+    /// to `dir_id`'s, resolved the same way [`Session::salvage_directory`]
+    /// resolves one -- by searching every directory reachable from the
+    /// root, live or deleted. Unlike `salvage_directory`, this replaces the
+    /// session's own notion of "current directory" rather than just
+    /// returning the entries, so every command built on `dir_entries`
+    /// (`ls`, `cat`/`get`, tab completion) keeps working against the new
+    /// location. Lets a shell session (or the `--root` startup option)
+    /// navigate into a surviving subtree even when the true root's own
+    /// entries are damaged, as long as some directory's `dir_id` can still
+    /// be found somewhere in the tree.
+    pub fn cd(&mut self, dir_id: u32) -> Result<()> {
+        let first_block = self.first_block_of_dir(dir_id)?;
+        self.dir_entries = self.read_directory_at(first_block)?;
+        Ok(())
+    }
+
+    /// Like [`Session::first_block_of_dir`], but for a caller like
+    /// [`Session::owners`] or [`Session::du`] whose `dir_id` is optional
+    /// and defaults to the volume root when not given. `None` goes straight
+    /// to the real root without the collision search
+    /// `first_block_of_dir(ROOT_DIR_ID)` does -- that search exists for a
+    /// caller who explicitly typed dir_id `0` and might have meant a
+    /// colliding subdirectory instead, which isn't in play when the caller
+    /// never named a dir_id at all.
+    fn first_block_of_dir_or_root(&mut self, dir_id: Option<u32>) -> Result<u32> {
+        match dir_id {
+            Some(id) => self.first_block_of_dir(id),
+            None => Ok(self.vol.dir_first_block()),
+        }
+    }
+
+    /// Resolve `dir_id` to the `first_block` of its directory table, by
+    /// searching every directory reachable from the root -- including
+    /// deleted ones, since a caller like [`Session::salvage_directory`]
+    /// specifically wants those too.
+    ///
+    /// `dir_id` is assigned positionally within each directory's own
+    /// listing (see [`crate::nwfs386::read_directory_entries`] and
+    /// [`crate::nwfs286`]'s equivalent) rather than being unique across the
+    /// whole volume, so slot 0 of *any* directory collides with
+    /// [`ROOT_DIR_ID`]. Resolving `ROOT_DIR_ID` straight to the volume root
+    /// without checking for that collision would silently send a caller
+    /// who meant "the subdirectory that happens to sit in slot 0 of its
+    /// parent" to the true root instead -- so when `dir_id == ROOT_DIR_ID`,
+    /// this still searches the tree for a colliding subdirectory and
+    /// returns [`NwfsError::AmbiguousDirId`] if one exists, rather than
+    /// guessing. A caller whose `dir_id` is merely a default rather than
+    /// something the user explicitly asked for should use
+    /// [`Session::first_block_of_dir_or_root`] instead, which skips this
+    /// search entirely for the common "no dir_id given" case.
+    fn first_block_of_dir(&mut self, dir_id: u32) -> Result<u32> {
+        if dir_id == ROOT_DIR_ID {
+            let colliding: Vec<String> = self
+                .manifest(DeletedFilter::All)?
+                .into_iter()
+                .filter_map(|(path, e)| match e {
+                    DirEntry::Directory(d) if d.dir_id == ROOT_DIR_ID => Some(path),
+                    _ => None,
+                })
+                .collect();
+            if colliding.is_empty() {
+                return Ok(self.vol.dir_first_block());
+            }
+            return Err(NwfsError::AmbiguousDirId {
+                dir_id,
+                paths: colliding,
+            });
+        }
+        self.manifest(DeletedFilter::All)?
+            .into_iter()
+            .find_map(|(_, e)| match e {
+                DirEntry::Directory(d) if d.dir_id == dir_id => Some(d.first_block),
+                _ => None,
+            })
+            .ok_or_else(|| NwfsError::Other(format!("no directory with id {dir_id} found")))
+    }
+
+    /// Resolve a directory id back to its full path by walking `parent_id`
+    /// links up to the root, among the currently-loaded entries. Returns
+    /// `None` if `dir_id` isn't a known directory or the chain of parents
+    /// cycles back on itself before reaching the root.
+    ///
+    /// This lives on `Session` rather than `LogicalVolume` because the
+    /// directory table it walks (`dir_entries`) is session state, not
+    /// volume state -- `LogicalVolume` only knows how to read blocks, not
+    /// how to interpret them as directory entries. Builds a `dir_id ->
+    /// (name, parent_id)` index once up front instead of re-scanning
+    /// `dir_entries` at every hop, so a deeply nested path costs one pass
+    /// over the table plus its own depth, not depth-squared.
+    pub fn path_of(&self, dir_id: u32) -> Option<String> {
+        if dir_id == ROOT_DIR_ID {
+            return Some(String::new());
+        }
+        let by_id: std::collections::HashMap<u32, (&str, u32)> = self
+            .dir_entries
+            .iter()
+            .filter_map(|e| match e {
+                DirEntry::Directory(d) => Some((d.dir_id, (d.name.as_str(), d.parent_id))),
+                _ => None,
+            })
+            .collect();
+
+        let mut components = Vec::new();
+        let mut current = dir_id;
+        let mut visited = std::collections::HashSet::new();
+        loop {
+            if current == ROOT_DIR_ID {
+                break;
+            }
+            if !visited.insert(current) {
+                return None; // cycle
+            }
+            let (name, parent_id) = *by_id.get(&current)?;
+            components.push(name.to_string());
+            current = parent_id;
+        }
+        components.reverse();
+        Some(components.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file chain that never touches the directory table's range is
+    /// pure -- the common case, and must not produce a false warning.
+    #[test]
+    fn directory_chain_impurity_is_none_when_ranges_dont_overlap() {
+        let fat = vec![FAT_END, FAT_END, 5, FAT_END, FAT_END, FAT_END];
+        assert_eq!(directory_chain_impurity(&fat, 2, 2), None);
+    }
+
+    /// A file chain link from outside the directory's range into a block
+    /// inside it is exactly the interleaving this check exists to catch.
+    #[test]
+    fn directory_chain_impurity_detects_a_file_chain_into_the_directory_range() {
+        let fat = vec![FAT_END, 2, FAT_END, FAT_END];
+        assert!(directory_chain_impurity(&fat, 2, 1).is_some());
+    }
+
+    /// A synthetic in-memory NWFS386 volume with a two-level directory tree
+    /// (`A/B`), a plain file at the root, and a deleted directory sharing a
+    /// name with a live one -- exercises [`Session::list_matching_dirs`]'s
+    /// path-segment descent and its deleted/live disambiguation in one
+    /// fixture, since both are driven by the same loop.
+    mod list_matching_dirs_tests {
+        use super::*;
+        use crate::dirent::attr;
+        use crate::hotfix::HotfixTable;
+        use crate::mbr::PartitionEntry;
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::io::Cursor;
+
+        const BLOCK_SIZE: u32 = 512;
+        const DIR_BLOCKS: u32 = crate::voltab::INITIAL_DIR_BLOCKS;
+
+        fn write_entry(
+            block: &mut [u8],
+            slot: usize,
+            name: &str,
+            first_block: u32,
+            parent_id: u32,
+            is_dir: bool,
+        ) {
+            let off = slot * crate::nwfs386::DIRECTORY_ENTRY_SIZE;
+            block[off + 0x04..off + 0x08].copy_from_slice(&first_block.to_le_bytes());
+            block[off + 0x08..off + 0x0c].copy_from_slice(&parent_id.to_le_bytes());
+            let attr_bits: u16 = if is_dir { attr::SUBDIRECTORY } else { 0 };
+            block[off + 0x10..off + 0x12].copy_from_slice(&attr_bits.to_le_bytes());
+            block[off + 0x12] = name.len() as u8;
+            block[off + 0x13..off + 0x13 + name.len()].copy_from_slice(name.as_bytes());
+        }
+
+        /// Builds the fixture described above and returns a [`Session`]
+        /// ready to call [`Session::list_matching_dirs`] on.
+        fn fixture() -> Session {
+            // Layout (in blocks): 0 reserved, 1 FAT, then a 16-block
+            // directory region for each of root/A/B/DUP-live/DUP-deleted,
+            // each starting right after the previous one ends.
+            let root_first = 2u32;
+            let a_first = root_first + DIR_BLOCKS;
+            let b_first = a_first + DIR_BLOCKS;
+            let dup_live_first = b_first + DIR_BLOCKS;
+            let dup_deleted_first = dup_live_first + DIR_BLOCKS;
+            let total_blocks = dup_deleted_first + DIR_BLOCKS;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            fn block_at(image: &mut [u8], block: u32) -> &mut [u8] {
+                let off = (block * BLOCK_SIZE) as usize;
+                &mut image[off..off + BLOCK_SIZE as usize]
+            }
+
+            write_entry(block_at(&mut image, root_first), 0, "A", a_first, 0, true);
+            write_entry(block_at(&mut image, root_first), 1, "FILE1", 0, 0, false);
+            write_entry(block_at(&mut image, root_first), 2, "DUP", dup_live_first, 0, true);
+            write_entry(
+                block_at(&mut image, root_first),
+                3,
+                "DUP",
+                dup_deleted_first,
+                crate::dirent::DIRID_AVAILABLE,
+                true,
+            );
+            write_entry(block_at(&mut image, a_first), 0, "B", b_first, 1, true);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat: Vec::new(),
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(Vec::new()),
+                warnings: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn descends_two_directory_levels() {
+            let mut session = fixture();
+            let matches = session.list_matching_dirs("A/B").unwrap();
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].0, "A/B");
+        }
+
+        #[test]
+        fn a_trailing_slash_does_not_change_the_result() {
+            let mut session = fixture();
+            let without = session.list_matching_dirs("A/B").unwrap();
+            let with = session.list_matching_dirs("A/B/").unwrap();
+            assert_eq!(without.len(), with.len());
+            assert_eq!(without[0].0, with[0].0);
+        }
+
+        /// `FILE1` is a plain file, not a directory, so a pattern that
+        /// tries to descend through it must match nothing rather than
+        /// erroring or silently treating the file as an empty directory.
+        #[test]
+        fn a_path_component_that_is_a_file_matches_nothing() {
+            let mut session = fixture();
+            let matches = session.list_matching_dirs("FILE1/ANYTHING").unwrap();
+            assert!(matches.is_empty());
+        }
+
+        /// Both a live and a deleted directory named `DUP` exist at the
+        /// root; only the live one should ever be reachable by name.
+        #[test]
+        fn a_deleted_entry_does_not_shadow_a_live_same_named_entry() {
+            let mut session = fixture();
+            let matches = session.list_matching_dirs("DUP").unwrap();
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].0, "DUP");
+        }
+
+        /// A second, smaller fixture with a root directory `A` and a single
+        /// child `B` inside it, for [`Session::orphans`] tests that need
+        /// control over `B`'s raw `parent_id` field.
+        fn fixture_with_child_parent_id(child_parent_id: u32) -> Session {
+            let root_first = 2u32;
+            let a_first = root_first + DIR_BLOCKS;
+            let total_blocks = a_first + DIR_BLOCKS;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            write_entry(&mut image[root_off..root_off + BLOCK_SIZE as usize], 0, "A", a_first, 0, true);
+            let a_off = (a_first * BLOCK_SIZE) as usize;
+            write_entry(&mut image[a_off..a_off + BLOCK_SIZE as usize], 0, "B", 0, child_parent_id, false);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat: Vec::new(),
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(Vec::new()),
+                warnings: Vec::new(),
+            }
+        }
+
+        /// `B`'s `parent_id` (corrupted to a value that matches no
+        /// directory anywhere in the reachable tree) must be reported as
+        /// an orphan grouped under that missing id, even though `B` itself
+        /// was found by descending into `A`'s own block chain.
+        #[test]
+        fn an_entry_with_an_unresolvable_parent_id_is_reported_as_an_orphan() {
+            const BOGUS_PARENT: u32 = 0xbad1;
+            let mut session = fixture_with_child_parent_id(BOGUS_PARENT);
+
+            let orphans = session.orphans().unwrap();
+            assert_eq!(orphans.len(), 1);
+            let (missing_parent, entries) = &orphans[0];
+            assert_eq!(*missing_parent, BOGUS_PARENT);
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].1.name(), "B");
+        }
+
+        /// The mirror image of the corruption case: when `B`'s `parent_id`
+        /// correctly names `A`'s `dir_id`, there's nothing to report.
+        #[test]
+        fn an_entry_with_a_correct_parent_id_is_not_an_orphan() {
+            let mut session = fixture_with_child_parent_id(0);
+            assert!(session.orphans().unwrap().is_empty());
+        }
+    }
+
+    /// A synthetic volume with a root directory, a two-block file, a
+    /// hotfix-redirected block, and some untouched space -- exercises all
+    /// three [`BlockState`] variants of [`Session::block_map`] in one
+    /// fixture.
+    mod block_map_tests {
+        use super::*;
+        use crate::hotfix::{HotfixEntry, HotfixTable};
+        use crate::mbr::PartitionEntry;
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::io::Cursor;
+
+        const BLOCK_SIZE: u32 = 512;
+        const DIR_BLOCKS: u32 = crate::voltab::INITIAL_DIR_BLOCKS;
+
+        fn fixture() -> Session {
+            // Layout (in blocks): 0 reserved, 1 FAT, 2..18 root directory,
+            // 18/19 never referenced by anything, 20/21 FILE1's chain, 22
+            // hotfix-redirected and so never walked even though nothing
+            // chains into it.
+            let root_first = 2u32;
+            let file_first = root_first + DIR_BLOCKS + 2;
+            let bad_block = file_first + 2;
+            let total_blocks = bad_block + 1;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            let off = root_off;
+            image[off + 0x04..off + 0x08].copy_from_slice(&file_first.to_le_bytes());
+            image[off + 0x08..off + 0x0c].copy_from_slice(&0u32.to_le_bytes()); // parent_id
+            image[off + 0x12] = 5; // name_len
+            image[off + 0x13..off + 0x18].copy_from_slice(b"FILE1");
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            let mut fat = vec![FAT_END; total_blocks as usize];
+            fat[file_first as usize] = file_first + 1;
+
+            Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat,
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(vec![HotfixEntry {
+                    original_block: bad_block,
+                    redirect_block: file_first,
+                }]),
+                warnings: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn classifies_directory_file_chain_free_and_bad_blocks() {
+            let mut session = fixture();
+            let root_first = 2u32;
+            let file_first = root_first + DIR_BLOCKS + 2;
+            let bad_block = file_first + 2;
+
+            let states = session.block_map().unwrap();
+
+            assert_eq!(states[root_first as usize], BlockState::Used);
+            assert_eq!(states[(root_first + DIR_BLOCKS - 1) as usize], BlockState::Used);
+            assert_eq!(states[(root_first + DIR_BLOCKS) as usize], BlockState::Free);
+            assert_eq!(states[file_first as usize], BlockState::Used);
+            assert_eq!(states[(file_first + 1) as usize], BlockState::Used);
+            assert_eq!(states[bad_block as usize], BlockState::Bad);
+        }
+    }
+
+    /// Two files whose FAT chains both run into the same block -- the
+    /// cross-linking [`Session::cross_linked_blocks`] exists to catch.
+    mod cross_linked_blocks_tests {
+        use super::*;
+        use crate::hotfix::HotfixTable;
+        use crate::mbr::PartitionEntry;
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::io::Cursor;
+
+        const BLOCK_SIZE: u32 = 512;
+        const DIR_BLOCKS: u32 = crate::voltab::INITIAL_DIR_BLOCKS;
+
+        fn write_entry(block: &mut [u8], slot: usize, name: &str, first_block: u32) {
+            let off = slot * crate::nwfs386::DIRECTORY_ENTRY_SIZE;
+            block[off + 0x04..off + 0x08].copy_from_slice(&first_block.to_le_bytes());
+            block[off + 0x12] = name.len() as u8;
+            block[off + 0x13..off + 0x13 + name.len()].copy_from_slice(name.as_bytes());
+        }
+
+        /// Layout (in blocks): 0 reserved, 1 FAT, 2..18 root directory with
+        /// two file entries, FILE1 and FILE2, whose chains both run into
+        /// `shared_block` before terminating.
+        fn fixture() -> Session {
+            let root_first = 2u32;
+            let file1_first = root_first + DIR_BLOCKS;
+            let file2_first = file1_first + 1;
+            let shared_block = file2_first + 1;
+            let total_blocks = shared_block + 1;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            let root_block = &mut image[root_off..root_off + BLOCK_SIZE as usize];
+            write_entry(root_block, 0, "FILE1", file1_first);
+            write_entry(root_block, 1, "FILE2", file2_first);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            let mut fat = vec![FAT_END; total_blocks as usize];
+            fat[file1_first as usize] = shared_block;
+            fat[file2_first as usize] = shared_block;
+
+            Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat,
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(vec![]),
+                warnings: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn reports_the_block_both_files_chain_into_and_names_both() {
+            let mut session = fixture();
+            let root_first = 2u32;
+            let file2_first = root_first + DIR_BLOCKS + 1;
+            let shared_block = file2_first + 1;
+
+            let cross_linked = session.cross_linked_blocks().unwrap();
+
+            assert_eq!(cross_linked.len(), 1);
+            assert_eq!(cross_linked[0].block, shared_block);
+            assert_eq!(cross_linked[0].paths, vec!["FILE1".to_string(), "FILE2".to_string()]);
+        }
+
+        #[test]
+        fn a_file_that_chains_nowhere_else_has_no_cross_links() {
+            let mut session = fixture();
+            let shared_block = 2u32 + DIR_BLOCKS + 2;
+            session.fat[shared_block as usize] = FAT_END;
+            // Sever FILE2's chain before it reaches the shared block.
+            let file2_first = 2u32 + DIR_BLOCKS + 1;
+            session.fat[file2_first as usize] = FAT_END;
+
+            let cross_linked = session.cross_linked_blocks().unwrap();
+
+            assert!(cross_linked.is_empty());
+        }
+
+        /// Every empty file's `first_block` is the [`FAT_END`] sentinel, not
+        /// a real block, so two unrelated empty files must not be reported
+        /// as cross-linked on block `0xffff_ffff`.
+        #[test]
+        fn two_empty_files_are_not_cross_linked_on_the_fat_end_sentinel() {
+            let root_first = 2u32;
+            let total_blocks = root_first + DIR_BLOCKS;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            let root_block = &mut image[root_off..root_off + BLOCK_SIZE as usize];
+            write_entry(root_block, 0, "EMPTY1", FAT_END);
+            write_entry(root_block, 1, "EMPTY2", FAT_END);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            let mut session = Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat: vec![FAT_END; total_blocks as usize],
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(vec![]),
+                warnings: Vec::new(),
+            };
+
+            let cross_linked = session.cross_linked_blocks().unwrap();
+
+            assert!(cross_linked.is_empty());
+        }
+    }
+
+    /// A root with two entries -- a filler file at slot 0 and a directory
+    /// `A` at slot 1, so `A`'s positional `dir_id` is `1` rather than `0`
+    /// and can't be confused with [`ROOT_DIR_ID`] -- containing one file,
+    /// for [`Session::cd`] tests. The slot-0-collision case this avoids has
+    /// its own dedicated fixture and test below.
+    mod cd_tests {
+        use super::*;
+        use crate::hotfix::HotfixTable;
+        use crate::mbr::PartitionEntry;
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::io::Cursor;
+
+        const BLOCK_SIZE: u32 = 512;
+        const DIR_BLOCKS: u32 = crate::voltab::INITIAL_DIR_BLOCKS;
+
+        fn write_entry(block: &mut [u8], slot: usize, name: &str, first_block: u32, is_dir: bool) {
+            let off = slot * crate::nwfs386::DIRECTORY_ENTRY_SIZE;
+            block[off + 0x04..off + 0x08].copy_from_slice(&first_block.to_le_bytes());
+            let attr_bits: u16 = if is_dir { crate::dirent::attr::SUBDIRECTORY } else { 0 };
+            block[off + 0x10..off + 0x12].copy_from_slice(&attr_bits.to_le_bytes());
+            block[off + 0x12] = name.len() as u8;
+            block[off + 0x13..off + 0x13 + name.len()].copy_from_slice(name.as_bytes());
+        }
+
+        fn fixture() -> Session {
+            let root_first = 2u32;
+            let a_first = root_first + DIR_BLOCKS;
+            let total_blocks = a_first + DIR_BLOCKS;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            write_entry(&mut image[root_off..root_off + BLOCK_SIZE as usize], 0, "FILLER", 0, false);
+            write_entry(&mut image[root_off..root_off + BLOCK_SIZE as usize], 1, "A", a_first, true);
+            let a_off = (a_first * BLOCK_SIZE) as usize;
+            write_entry(&mut image[a_off..a_off + BLOCK_SIZE as usize], 0, "INSIDE", 0, false);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat: Vec::new(),
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(Vec::new()),
+                warnings: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn cd_into_a_non_root_directory_replaces_the_loaded_entries() {
+            let mut session = fixture();
+            session.cd(1).unwrap();
+            assert_eq!(session.dir_entries.len(), 1);
+            assert_eq!(session.dir_entries[0].name(), "INSIDE");
+        }
+
+        #[test]
+        fn cd_with_an_unknown_dir_id_is_an_error() {
+            let mut session = fixture();
+            assert!(session.cd(0xdead).is_err());
+        }
+
+        #[test]
+        fn cd_back_to_root_dir_id_restores_the_root_listing() {
+            let mut session = fixture();
+            session.cd(1).unwrap();
+            session.cd(ROOT_DIR_ID).unwrap();
+            assert_eq!(session.dir_entries.len(), 2);
+        }
+
+        /// `dir_id` is positional within each directory's own listing (see
+        /// [`crate::nwfs386::read_directory_entries`]), so a subdirectory
+        /// sitting in slot 0 of its parent gets the same `dir_id` as
+        /// [`ROOT_DIR_ID`]. `cd(ROOT_DIR_ID)` must flag this rather than
+        /// silently landing on the true root when it isn't what the caller
+        /// meant.
+        #[test]
+        fn cd_to_root_dir_id_errors_when_a_subdirectory_collides_with_it() {
+            let root_first = 2u32;
+            let a_first = root_first + DIR_BLOCKS;
+            let total_blocks = a_first + DIR_BLOCKS;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            write_entry(&mut image[root_off..root_off + BLOCK_SIZE as usize], 0, "A", a_first, true);
+            let a_off = (a_first * BLOCK_SIZE) as usize;
+            write_entry(&mut image[a_off..a_off + BLOCK_SIZE as usize], 0, "INSIDE", 0, false);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            let mut session = Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat: Vec::new(),
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(Vec::new()),
+                warnings: Vec::new(),
+            };
+
+            assert!(matches!(
+                session.cd(ROOT_DIR_ID),
+                Err(NwfsError::AmbiguousDirId { dir_id, .. }) if dir_id == ROOT_DIR_ID
+            ));
+        }
+    }
+
+    /// A root with two files owned by the same id and a subdirectory
+    /// holding one file owned by a different id, for [`Session::owners`]
+    /// tests.
+    mod owners_tests {
+        use super::*;
+        use crate::hotfix::HotfixTable;
+        use crate::mbr::PartitionEntry;
+        use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+        use std::io::Cursor;
+
+        const BLOCK_SIZE: u32 = 512;
+        const DIR_BLOCKS: u32 = crate::voltab::INITIAL_DIR_BLOCKS;
+
+        fn write_file(block: &mut [u8], slot: usize, name: &str, length: u32, owner_id: u16) {
+            let off = slot * crate::nwfs386::DIRECTORY_ENTRY_SIZE;
+            block[off + 0x0c..off + 0x10].copy_from_slice(&length.to_le_bytes());
+            block[off + 0x12] = name.len() as u8;
+            block[off + 0x13..off + 0x13 + name.len()].copy_from_slice(name.as_bytes());
+            block[off + 0x26..off + 0x28].copy_from_slice(&owner_id.to_le_bytes());
+        }
+
+        fn write_dir(block: &mut [u8], slot: usize, name: &str, first_block: u32) {
+            let off = slot * crate::nwfs386::DIRECTORY_ENTRY_SIZE;
+            block[off + 0x04..off + 0x08].copy_from_slice(&first_block.to_le_bytes());
+            block[off + 0x10..off + 0x12].copy_from_slice(&crate::dirent::attr::SUBDIRECTORY.to_le_bytes());
+            block[off + 0x12] = name.len() as u8;
+            block[off + 0x13..off + 0x13 + name.len()].copy_from_slice(name.as_bytes());
+        }
+
+        fn fixture() -> Session {
+            let root_first = 2u32;
+            let sub_first = root_first + DIR_BLOCKS;
+            let total_blocks = sub_first + DIR_BLOCKS;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            let root_block = &mut image[root_off..root_off + BLOCK_SIZE as usize];
+            write_file(root_block, 0, "A.TXT", 100, 7);
+            write_file(root_block, 1, "B.TXT", 50, 7);
+            write_dir(root_block, 2, "SUB", sub_first);
+            let sub_off = (sub_first * BLOCK_SIZE) as usize;
+            write_file(&mut image[sub_off..sub_off + BLOCK_SIZE as usize], 0, "C.TXT", 30, 9);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat: Vec::new(),
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(Vec::new()),
+                warnings: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn tallies_file_count_and_bytes_per_owner_across_the_whole_tree() {
+            let mut session = fixture();
+            let summaries = session.owners(None).unwrap();
+
+            assert_eq!(summaries.len(), 2);
+            assert_eq!(summaries[0].owner_id, 7);
+            assert_eq!(summaries[0].file_count, 2);
+            assert_eq!(summaries[0].total_bytes, 150);
+            assert_eq!(summaries[1].owner_id, 9);
+            assert_eq!(summaries[1].file_count, 1);
+            assert_eq!(summaries[1].total_bytes, 30);
+        }
+
+        /// A root whose only entry is a subdirectory sits in slot 0, so
+        /// that subdirectory's `dir_id` collides with [`ROOT_DIR_ID`] --
+        /// exactly the common layout [`Session::first_block_of_dir`]'s
+        /// collision search flags. [`Session::owners`]'s implicit default
+        /// (no `dir_id` given) must not trip that search at all; only an
+        /// explicit `dir_id` of `0` should.
+        #[test]
+        fn implicit_default_root_is_not_treated_as_an_ambiguous_explicit_dir_id() {
+            let root_first = 2u32;
+            let sub_first = root_first + DIR_BLOCKS;
+            let total_blocks = sub_first + DIR_BLOCKS;
+
+            let mut image = vec![0u8; (total_blocks * BLOCK_SIZE) as usize];
+            let root_off = (root_first * BLOCK_SIZE) as usize;
+            let root_block = &mut image[root_off..root_off + BLOCK_SIZE as usize];
+            write_dir(root_block, 0, "A", sub_first);
+            let sub_off = (sub_first * BLOCK_SIZE) as usize;
+            write_file(&mut image[sub_off..sub_off + BLOCK_SIZE as usize], 0, "INSIDE.TXT", 10, 1);
+
+            let info = VolumeInfo {
+                name: "SYS".to_string(),
+                total_blocks,
+                volume_number: 0,
+            };
+            let segments = vec![Segment {
+                segment_num: 0,
+                block_size: BLOCK_SIZE,
+                first_block: 0,
+                num_blocks: total_blocks,
+                image_offset: 0,
+            }];
+            let image_len = image.len() as u64;
+            let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+
+            let mut session = Session {
+                image_path: String::new(),
+                format: Format::Nwfs386,
+                partition: PartitionEntry {
+                    index: 0,
+                    partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+                    lba_start: 0,
+                    num_sectors: total_blocks * (BLOCK_SIZE / 512),
+                },
+                vol,
+                file: Box::new(Cursor::new(image)),
+                fat: Vec::new(),
+                dir_entries: Vec::new(),
+                hotfix: HotfixTable::from_entries(Vec::new()),
+                warnings: Vec::new(),
+            };
+
+            let summaries = session.owners(None).unwrap();
+            assert_eq!(summaries.len(), 1);
+            assert_eq!(summaries[0].file_count, 1);
+
+            assert!(matches!(
+                session.owners(Some(ROOT_DIR_ID)),
+                Err(NwfsError::AmbiguousDirId { dir_id, .. }) if dir_id == ROOT_DIR_ID
+            ));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! An abstraction over where a session's bytes actually come from, so the
+//! format parsers don't care whether they're reading a single image file
+//! or several [`crate::split::SplitImage`] chunks glued together into one
+//! logical stream.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::{NwfsError, Result};
+use crate::split::SplitImage;
+
+/// Anything the format parsers can read and seek within as if it were one
+/// contiguous image.
+pub trait Source: Read + Seek {
+    /// Total length of the source, in bytes.
+    fn total_len(&mut self) -> std::io::Result<u64> {
+        let pos = self.stream_position()?;
+        let len = self.seek(SeekFrom::End(0))?;
+        self.seek(SeekFrom::Start(pos))?;
+        Ok(len)
+    }
+}
+
+impl<T: Read + Seek> Source for T {}
+
+/// Open `path` as a [`Source`]: a single file, or -- if `path` is one of a
+/// set of numbered chunks like `image.001`, `image.002`, ... -- all of
+/// them concatenated into one logical stream.
+pub fn open_source(path: &Path) -> Result<Box<dyn Source>> {
+    if let Some(split) = SplitImage::detect(path).map_err(|source| NwfsError::Io {
+        path: path.to_path_buf(),
+        source,
+    })? {
+        return Ok(Box::new(split));
+    }
+    let file = std::fs::File::open(path).map_err(|source| NwfsError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(Box::new(file))
+}
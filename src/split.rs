@@ -0,0 +1,176 @@
+//! Support for images split into fixed-size numbered chunks (`.001`,
+//! `.002`, ...) by imaging tools that cap how large a single output file
+//! can be. [`SplitImage`] presents the chunks as one logical [`Read`] +
+//! [`Seek`] stream, the same interface a single image file offers, so the
+//! rest of the crate never has to know a capture was split at all.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// One chunk's place in the logical address space.
+struct Chunk {
+    path: PathBuf,
+    start: u64,
+    len: u64,
+}
+
+pub struct SplitImage {
+    chunks: Vec<Chunk>,
+    total_len: u64,
+    current: Option<(usize, File)>,
+    pos: u64,
+}
+
+impl SplitImage {
+    /// If `path`'s extension is a zero-padded chunk number (e.g.
+    /// `image.001`) with at least one numbered sibling present, probe for
+    /// every following chunk and return a `SplitImage` spanning all of
+    /// them in order, starting from `path`. Returns `Ok(None)` for a path
+    /// that isn't part of a numbered chunk set, so callers can fall back
+    /// to opening it as a single file.
+    pub fn detect(path: &Path) -> io::Result<Option<Self>> {
+        let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+            return Ok(None);
+        };
+        if ext.is_empty() || !ext.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok(None);
+        }
+        let width = ext.len();
+        let Ok(mut num) = ext.parse::<u64>() else {
+            return Ok(None);
+        };
+
+        let mut chunks = Vec::new();
+        let mut offset = 0u64;
+        let mut current_path = path.to_path_buf();
+        while let Ok(metadata) = std::fs::metadata(&current_path) {
+            let len = metadata.len();
+            chunks.push(Chunk {
+                path: current_path.clone(),
+                start: offset,
+                len,
+            });
+            offset += len;
+            num += 1;
+            current_path = path.with_extension(format!("{num:0width$}"));
+        }
+        if chunks.len() < 2 {
+            // A lone numbered file with no sibling is just a file; let the
+            // caller open it normally instead of treating it as "split".
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            chunks,
+            total_len: offset,
+            current: None,
+            pos: 0,
+        }))
+    }
+
+    fn chunk_for(&self, pos: u64) -> Option<usize> {
+        self.chunks.iter().position(|c| pos >= c.start && pos < c.start + c.len)
+    }
+
+    fn open_chunk(&mut self, index: usize) -> io::Result<&mut File> {
+        if self.current.as_ref().map(|(i, _)| *i) != Some(index) {
+            let file = File::open(&self.chunks[index].path)?;
+            self.current = Some((index, file));
+        }
+        Ok(&mut self.current.as_mut().unwrap().1)
+    }
+}
+
+impl Read for SplitImage {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.total_len {
+            return Ok(0);
+        }
+        let Some(index) = self.chunk_for(self.pos) else {
+            return Ok(0);
+        };
+        let chunk = &self.chunks[index];
+        let offset_in_chunk = self.pos - chunk.start;
+        let remaining_in_chunk = chunk.len - offset_in_chunk;
+        let take = (buf.len() as u64).min(remaining_in_chunk) as usize;
+
+        let file = self.open_chunk(index)?;
+        file.seek(SeekFrom::Start(offset_in_chunk))?;
+        let n = file.read(&mut buf[..take])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for SplitImage {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i64,
+            SeekFrom::End(p) => self.total_len as i64 + p,
+            SeekFrom::Current(p) => self.pos as i64 + p,
+        };
+        if new_pos < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "seek before byte 0"));
+        }
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Write `data` split into `chunk_size`-byte files named
+    /// `<stem>.001`, `.002`, ... under the system temp directory, and
+    /// return the path of the first chunk.
+    fn write_chunks(stem: &str, data: &[u8], chunk_size: usize) -> PathBuf {
+        let dir = std::env::temp_dir();
+        let mut first = None;
+        for (i, piece) in data.chunks(chunk_size).enumerate() {
+            let path = dir.join(format!("{stem}.{:03}", i + 1));
+            let mut f = File::create(&path).unwrap();
+            f.write_all(piece).unwrap();
+            if first.is_none() {
+                first = Some(path);
+            }
+        }
+        first.unwrap()
+    }
+
+    /// Reading across a chunk boundary, and seeking to an arbitrary offset
+    /// that lands in a later chunk, must both reproduce exactly what a
+    /// single unsplit file would have returned.
+    #[test]
+    fn reads_and_seeks_transparently_across_chunk_boundaries() {
+        let data: Vec<u8> = (0..250u32).map(|i| (i % 256) as u8).collect();
+        let first = write_chunks("nwfs_split_test", &data, 100);
+
+        let mut split = SplitImage::detect(&first).unwrap().unwrap();
+        assert_eq!(split.total_len, data.len() as u64);
+
+        let mut all = Vec::new();
+        split.read_to_end(&mut all).unwrap();
+        assert_eq!(all, data);
+
+        split.seek(SeekFrom::Start(150)).unwrap();
+        let mut buf = [0u8; 20];
+        split.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, data[150..170]);
+
+        for i in 1..=3 {
+            std::fs::remove_file(std::env::temp_dir().join(format!("nwfs_split_test.{i:03}"))).ok();
+        }
+    }
+
+    /// A path whose extension happens to be numeric but has no sibling
+    /// chunk isn't part of a split set -- it should be left for the
+    /// caller to open as an ordinary file.
+    #[test]
+    fn detect_returns_none_without_a_sibling_chunk() {
+        let path = write_chunks("nwfs_split_lone", b"just one chunk", 1000);
+        assert!(SplitImage::detect(&path).unwrap().is_none());
+        std::fs::remove_file(&path).ok();
+    }
+}
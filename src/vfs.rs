@@ -0,0 +1,44 @@
+//! A minimal read-only filesystem adapter over a [`Session`], for embedding
+//! this crate into tools that already consume a generic VFS/object-store
+//! trait instead of reimplementing NetWare directory traversal themselves.
+//!
+//! This is deliberately a small custom trait rather than an adapter for a
+//! specific external VFS crate -- it covers the two operations (list every
+//! file, read one by path) that such an adapter needs to forward, and
+//! nothing else. A caller that needs richer access (directories, metadata,
+//! deleted files) should use [`Session`] directly; this trait only exists to
+//! be implemented against whatever trait the consuming tool expects.
+//!
+//! Gated behind the `vfs` feature since most consumers of this crate never
+//! need it.
+
+use crate::error::{NwfsError, Result};
+use crate::session::Session;
+
+/// A minimal read-only filesystem: list every file's path, and read one by
+/// path.
+pub trait ReadOnlyFs {
+    /// Every file's path relative to the volume root, `/`-separated, in the
+    /// same form returned by [`Session::file_tree`].
+    fn list(&mut self) -> Result<Vec<String>>;
+
+    /// Read a file's contents by its path as returned from
+    /// [`ReadOnlyFs::list`].
+    fn read(&mut self, path: &str) -> Result<Vec<u8>>;
+}
+
+impl ReadOnlyFs for Session {
+    fn list(&mut self) -> Result<Vec<String>> {
+        Ok(self.file_tree()?.into_iter().map(|(path, _)| path).collect())
+    }
+
+    fn read(&mut self, path: &str) -> Result<Vec<u8>> {
+        let item = self
+            .file_tree()?
+            .into_iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, item)| item)
+            .ok_or_else(|| NwfsError::Other(format!("'{path}' not found")))?;
+        self.read_file(&item)
+    }
+}
@@ -0,0 +1,740 @@
+//! The Volume Segment Table: a small table near the start of a NetWare
+//! partition that records, for every volume segment stored in the
+//! partition, which volume it belongs to and where its blocks live.
+
+use std::io::SeekFrom;
+
+use crate::bytes::u32_le;
+use crate::error::{NwfsError, Result};
+use crate::mbr::PartitionEntry;
+use crate::source::Source;
+use crate::volume::{LogicalVolume, Segment, VolumeInfo};
+
+/// Byte offset of the table within the partition (block 0 is reserved for
+/// the hotfix/mirror redirection area).
+const VOLUME_TABLE_OFFSET: u64 = 512;
+const ENTRY_SIZE: usize = 40;
+const NAME_FIELD_LEN: usize = 15;
+
+/// Number of directory blocks read for a directory table, as one fixed
+/// contiguous span from its first block; no FAT chain is followed to
+/// extend it.
+pub const INITIAL_DIR_BLOCKS: u32 = 16;
+
+/// Sane upper bound on the number of volume segment entries a table can
+/// declare. NetWare partitions hold a small handful of volumes at most; a
+/// `num_volumes` field above this is a sign the header was matched by luck
+/// on otherwise-corrupt data, not a real (if unusually large) table, and
+/// parsing further would just turn into a huge allocation followed by a
+/// confusing EOF error.
+const MAX_SANE_VOLUMES: u32 = 4096;
+
+#[derive(Debug, Clone)]
+pub struct VolumeSegmentEntry {
+    pub name: String,
+    pub volume_number: u32,
+    pub segment_num: u32,
+    pub num_segments_total: u32,
+    pub start_sector: u32,
+    pub num_sectors: u32,
+    pub block_size: u32,
+}
+
+/// Read the volume segment table for `partition`. Entries are returned in
+/// on-disk order, which is not necessarily grouped by volume or sorted by
+/// segment number.
+///
+/// This table's header is a bare `num_volumes` count, not a magic string
+/// -- unlike some other NetWare structures (see [`crate::nss::detect`]),
+/// there's nothing here to do a fuzzy match against, and no documented
+/// backup copy of the table to fall back to if this one is damaged. The
+/// closest thing to corruption detection is [`MAX_SANE_VOLUMES`] below:
+/// a `num_volumes` this large means the read landed on garbage rather
+/// than a real (if unusually large) table. `--lenient` elsewhere in this
+/// crate recovers from a table that parses but doesn't add up (missing
+/// or ambiguous segments); it can't help here, because a bad count means
+/// we don't know where the real entries even start.
+pub fn read_volume_table(file: &mut dyn Source, partition: &PartitionEntry) -> Result<(Vec<VolumeSegmentEntry>, Vec<String>)> {
+    let io_err = |source: std::io::Error| NwfsError::Io {
+        path: std::path::PathBuf::new(),
+        source,
+    };
+
+    file.seek(SeekFrom::Start(partition.byte_offset() + VOLUME_TABLE_OFFSET))
+        .map_err(io_err)?;
+    let mut header = [0u8; 4];
+    file.read_exact(&mut header).map_err(io_err)?;
+    let num_volumes = u32_le(&header, 0);
+    if num_volumes > MAX_SANE_VOLUMES {
+        return Err(NwfsError::TooManyVolumes {
+            num_volumes,
+            max: MAX_SANE_VOLUMES,
+        });
+    }
+
+    let mut entries = Vec::with_capacity(num_volumes as usize);
+    let mut warnings = Vec::new();
+    let mut buf = [0u8; ENTRY_SIZE];
+    for _ in 0..num_volumes {
+        file.read_exact(&mut buf).map_err(io_err)?;
+        let name_len_raw = buf[0] as usize;
+        let name_len = name_len_raw.min(NAME_FIELD_LEN);
+        let mut off = 1 + NAME_FIELD_LEN;
+        let volume_number = u32_le(&buf, off);
+        off += 4;
+        let segment_num = u32_le(&buf, off);
+        off += 4;
+        let num_segments_total = u32_le(&buf, off);
+        off += 4;
+        let start_sector = u32_le(&buf, off);
+        off += 4;
+        let num_sectors = u32_le(&buf, off);
+
+        if name_len_raw > NAME_FIELD_LEN {
+            warnings.push(format!(
+                "segment {segment_num} of volume_number {volume_number} reports a name length of {name_len_raw}, more than the {NAME_FIELD_LEN}-byte name field holds; truncating"
+            ));
+        }
+        let name = String::from_utf8_lossy(&buf[1..1 + name_len]).into_owned();
+        let name = if name.is_empty() {
+            warnings.push(format!(
+                "segment {segment_num} of volume_number {volume_number} has an empty name; using a placeholder so it stays addressable"
+            ));
+            format!("VOL{segment_num}")
+        } else {
+            name
+        };
+        off += 4;
+        let block_size = u32_le(&buf, off);
+
+        entries.push(VolumeSegmentEntry {
+            name,
+            volume_number,
+            segment_num,
+            num_segments_total,
+            start_sector,
+            num_sectors,
+            block_size,
+        });
+    }
+    Ok((entries, warnings))
+}
+
+/// How to pick a volume out of a partition's volume segment table, for
+/// callers that don't already know its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VolumeSelector {
+    /// Pick the first volume found, in table order.
+    #[default]
+    Auto,
+    /// Pick the volume whose entries report this `volume_number`,
+    /// regardless of `name` -- useful when the name is corrupt or
+    /// duplicated across segments from different disks.
+    ById(u32),
+}
+
+/// Resolve `selector` to the name of one volume's table entries, for a
+/// subsequent call to [`build_volume_lenient`]. `Auto` returns the first
+/// entry found; `ById` returns the entry matching that `volume_number` and
+/// errors out if none does.
+pub fn select_volume(entries: &[VolumeSegmentEntry], selector: VolumeSelector) -> Result<&VolumeSegmentEntry> {
+    match selector {
+        VolumeSelector::Auto => entries.first().ok_or_else(|| NwfsError::Other("no volumes found".into())),
+        VolumeSelector::ById(id) => entries.iter().find(|e| e.volume_number == id).ok_or(NwfsError::NoVolumeWithId { id }),
+    }
+}
+
+/// Assemble a [`LogicalVolume`] out of every table entry sharing `name`,
+/// ordered by `segment_num`.
+pub fn build_volume(
+    partition: &PartitionEntry,
+    entries: &[VolumeSegmentEntry],
+    name: &str,
+    image_len: u64,
+) -> Result<LogicalVolume> {
+    let (vol, warnings) = build_volume_lenient(partition, entries, name, image_len, false, None)?;
+    debug_assert!(warnings.is_empty());
+    Ok(vol)
+}
+
+/// Like [`build_volume`], but with `lenient = true` a `block_size`
+/// disagreement between segments produces a warning (returned alongside
+/// the volume) instead of an error; the volume's own block count is always
+/// computed using the first segment's `block_size` (or, in lenient mode,
+/// another segment's when the first one's is 0), so a mismatched
+/// segment can't throw off every later block's address.
+///
+/// `segment_order`, when given, is a last-resort escape hatch for a volume
+/// whose `segment_num`/`num_segments_total` fields are themselves corrupt
+/// and so can't be trusted to derive the assembly order or detect missing
+/// segments: each value is an index into the entries matching `name` (in
+/// their on-disk table order, *before* any `segment_num` sort), and the
+/// volume is assembled by concatenating them in exactly that order instead.
+/// This bypasses the missing-segment check entirely, since that check is
+/// itself built on the same `segment_num`/`num_segments_total` fields the
+/// override exists to work around -- a wrong order here silently produces a
+/// volume with scrambled or missing data, not an error, so it should only
+/// be used once the correct order has been worked out by other means (e.g.
+/// comparing directory contents across candidate orderings).
+pub fn build_volume_lenient(
+    partition: &PartitionEntry,
+    entries: &[VolumeSegmentEntry],
+    name: &str,
+    image_len: u64,
+    lenient: bool,
+    segment_order: Option<&[u32]>,
+) -> Result<(LogicalVolume, Vec<String>)> {
+    let mut matching: Vec<&VolumeSegmentEntry> = entries.iter().filter(|e| e.name == name).collect();
+    if matching.is_empty() {
+        return Err(NwfsError::Other(format!("no volume named '{name}' found")));
+    }
+
+    let mut warnings = Vec::new();
+
+    // `name` alone isn't a reliable volume identity -- two unrelated
+    // partitions (e.g. from different physical disks) can both have a
+    // volume called "DATA". `volume_number` is what actually distinguishes
+    // them, so when matching entries disagree on it, the segments don't
+    // all belong to the same volume and must not be merged together.
+    let mut volume_numbers: Vec<u32> = matching.iter().map(|e| e.volume_number).collect();
+    volume_numbers.sort_unstable();
+    volume_numbers.dedup();
+    if volume_numbers.len() > 1 {
+        let err = NwfsError::AmbiguousVolumeName {
+            name: name.to_string(),
+            volume_numbers: volume_numbers.clone(),
+        };
+        if lenient {
+            let chosen = volume_numbers[0];
+            warnings.push(format!("{err}; using volume_number {chosen} and ignoring the others"));
+            matching.retain(|e| e.volume_number == chosen);
+        } else {
+            return Err(err);
+        }
+    }
+
+    let matching = match segment_order {
+        Some(order) => {
+            let chosen: Result<Vec<&VolumeSegmentEntry>> = order
+                .iter()
+                .map(|&i| {
+                    matching.get(i as usize).copied().ok_or_else(|| NwfsError::InvalidSegmentOrder {
+                        name: name.to_string(),
+                        order: order.to_vec(),
+                        num_segments: matching.len(),
+                    })
+                })
+                .collect();
+            let chosen = chosen?;
+            let mut seen = std::collections::BTreeSet::new();
+            if chosen.len() != matching.len() || !order.iter().all(|i| seen.insert(*i)) {
+                return Err(NwfsError::InvalidSegmentOrder {
+                    name: name.to_string(),
+                    order: order.to_vec(),
+                    num_segments: matching.len(),
+                });
+            }
+            warnings.push(format!(
+                "segment order for volume '{name}' manually overridden to {order:?} (indices into the {} matched entries in table order); ignoring segment_num and the missing-segment check",
+                matching.len()
+            ));
+            chosen
+        }
+        None => {
+            let expected_segments = matching[0].num_segments_total;
+            let present: std::collections::BTreeSet<u32> = matching.iter().map(|e| e.segment_num).collect();
+            let missing: Vec<u32> = (0..expected_segments).filter(|n| !present.contains(n)).collect();
+            if !missing.is_empty() || present.len() != matching.len() {
+                let err = NwfsError::IncompleteVolumeSegments {
+                    name: name.to_string(),
+                    expected: expected_segments,
+                    found: present.len() as u32,
+                    missing,
+                };
+                if lenient {
+                    warnings.push(err.to_string());
+                } else {
+                    return Err(err);
+                }
+            }
+
+            let mut sorted = matching;
+            sorted.sort_by_key(|e| e.segment_num);
+            sorted
+        }
+    };
+    // A `block_size` of 0 can't be used as a divisor below, and isn't a
+    // real NetWare block size anyone would configure -- it only shows up
+    // from a corrupt table entry. In lenient mode, fall back to another
+    // matched entry's `block_size` if one is usable; otherwise (or in
+    // strict mode) there's nothing trustworthy left to divide by.
+    let volume_block_size = if matching[0].block_size != 0 {
+        matching[0].block_size
+    } else {
+        let err = NwfsError::ZeroBlockSize { name: name.to_string() };
+        match matching.iter().find(|e| e.block_size != 0) {
+            Some(fallback) if lenient => {
+                warnings.push(format!(
+                    "{err}; using segment {}'s block_size ({}) instead",
+                    fallback.segment_num, fallback.block_size
+                ));
+                fallback.block_size
+            }
+            _ => return Err(err),
+        }
+    };
+
+    // Some volumes record each segment's own sector count in `num_sectors`;
+    // others repeat the volume's *total* sector count in every entry. When
+    // every matching entry agrees on `num_sectors` and there's more than
+    // one of them, assume the latter and split the total evenly instead of
+    // (wrongly) giving every segment the full volume's worth of blocks.
+    let sectors_are_total = matching.len() > 1 && matching.iter().all(|e| e.num_sectors == matching[0].num_sectors);
+    let per_segment_sectors = if sectors_are_total {
+        matching[0].num_sectors / matching.len() as u32
+    } else {
+        0 // unused; each entry's own num_sectors is used below
+    };
+
+    let mut segments = Vec::with_capacity(matching.len());
+    let mut next_first_block = 0u32;
+    let mut total_blocks = 0u32;
+    for e in &matching {
+        let sectors = if sectors_are_total { per_segment_sectors } else { e.num_sectors };
+        let num_blocks = sectors * 512 / volume_block_size;
+        segments.push(Segment {
+            segment_num: e.segment_num,
+            // A segment's own `block_size` of 0 is the same corruption
+            // `volume_block_size` above already substituted for -- carry
+            // that substitute forward here too, rather than letting the
+            // raw zero reach `LogicalVolume::new`'s mismatch check.
+            block_size: if e.block_size != 0 { e.block_size } else { volume_block_size },
+            first_block: next_first_block,
+            num_blocks,
+            image_offset: partition.byte_offset() + u64::from(e.start_sector) * 512,
+        });
+        next_first_block += num_blocks;
+        total_blocks += num_blocks;
+    }
+
+    let info = VolumeInfo {
+        name: name.to_string(),
+        total_blocks,
+        volume_number: matching[0].volume_number,
+    };
+    if lenient {
+        let (vol, mut build_warnings) = LogicalVolume::new_lenient(info, segments, image_len)?;
+        warnings.append(&mut build_warnings);
+        Ok((vol, warnings))
+    } else {
+        LogicalVolume::new(info, segments, image_len).map(|vol| (vol, warnings))
+    }
+}
+
+/// One logical volume's worth of segments, as grouped by [`list_volumes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeSummary {
+    pub name: String,
+    pub volume_number: u32,
+    pub num_segments: u32,
+    pub total_sectors: u64,
+}
+
+/// Group raw volume segment table `entries` into one summary per logical
+/// volume, keyed by `volume_number` rather than `name` -- two different
+/// volumes can share a name (or both get the same `VOL<segment_num>`
+/// placeholder a zero-length name field is given), but `volume_number` is what actually
+/// distinguishes them, the same rule [`build_volume_lenient`] applies when
+/// it finds more than one `volume_number` behind a chosen name.
+pub fn list_volumes(entries: &[VolumeSegmentEntry]) -> Vec<VolumeSummary> {
+    let mut volume_numbers: Vec<u32> = entries.iter().map(|e| e.volume_number).collect();
+    volume_numbers.sort_unstable();
+    volume_numbers.dedup();
+
+    volume_numbers
+        .into_iter()
+        .map(|volume_number| {
+            let group: Vec<&VolumeSegmentEntry> = entries.iter().filter(|e| e.volume_number == volume_number).collect();
+            VolumeSummary {
+                name: group[0].name.clone(),
+                volume_number,
+                num_segments: group.len() as u32,
+                total_sectors: group.iter().map(|e| u64::from(e.num_sectors)).sum(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mbr::PartitionEntry;
+    use std::io::Write;
+
+    /// A garbage `num_volumes` (e.g. the table header matched by luck on
+    /// otherwise-corrupt data) must be rejected up front with a descriptive
+    /// error, rather than attempting a huge allocation and looping until a
+    /// confusing EOF turns up partway through.
+    #[test]
+    fn rejects_implausibly_large_num_volumes() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        let path = std::env::temp_dir().join(format!("nwfs_voltab_test_{}.img", std::process::id()));
+        {
+            let mut f = std::fs::File::create(&path).unwrap();
+            let mut bytes = vec![0u8; (VOLUME_TABLE_OFFSET + 4) as usize];
+            bytes[VOLUME_TABLE_OFFSET as usize..].copy_from_slice(&0x4141_4141u32.to_le_bytes());
+            f.write_all(&bytes).unwrap();
+        }
+
+        let mut file = std::fs::File::open(&path).unwrap();
+        let result = read_volume_table(&mut file, &partition);
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(result, Err(NwfsError::TooManyVolumes { .. })));
+    }
+
+    /// A segment entry with a zero-length name would otherwise parse to an
+    /// empty `name` that can never be selected by name, making the volume
+    /// unreachable. It must come back with a synthesized placeholder name
+    /// instead, plus a warning explaining why.
+    #[test]
+    fn synthesizes_a_placeholder_name_for_a_zero_length_name_field() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        let path = std::env::temp_dir().join(format!("nwfs_voltab_test_{}.img", std::process::id()));
+        {
+            let mut f = std::fs::File::create(&path).unwrap();
+            let mut bytes = vec![0u8; (VOLUME_TABLE_OFFSET + 4) as usize + ENTRY_SIZE];
+            bytes[VOLUME_TABLE_OFFSET as usize..VOLUME_TABLE_OFFSET as usize + 4].copy_from_slice(&1u32.to_le_bytes());
+            let entry_off = VOLUME_TABLE_OFFSET as usize + 4;
+            // name_len = 0, rest of the entry (volume_number, segment_num,
+            // num_segments_total, start_sector, num_sectors, block_size)
+            // left zeroed except segment_num, which is set to 3 so the
+            // placeholder name is distinguishable from a coincidental zero.
+            bytes[entry_off] = 0;
+            bytes[entry_off + 1 + NAME_FIELD_LEN + 4..entry_off + 1 + NAME_FIELD_LEN + 8].copy_from_slice(&3u32.to_le_bytes());
+            f.write_all(&bytes).unwrap();
+        }
+
+        let mut file = std::fs::File::open(&path).unwrap();
+        let (entries, warnings) = read_volume_table(&mut file, &partition).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "VOL3");
+        assert!(warnings.iter().any(|w| w.contains("empty name")));
+    }
+
+    fn entry(segment_num: u32, total: u32, start_sector: u32, num_sectors: u32) -> VolumeSegmentEntry {
+        VolumeSegmentEntry {
+            name: "SYS".to_string(),
+            volume_number: 0,
+            segment_num,
+            num_segments_total: total,
+            start_sector,
+            num_sectors,
+            block_size: 4096,
+        }
+    }
+
+    /// A volume spanning many segments (more than the "a handful" that a
+    /// fixed-size header would assume) must still assemble into one
+    /// contiguous logical block range, with later segments' blocks
+    /// resolving correctly -- there's no hardcoded cap on segment count
+    /// anywhere in this path.
+    #[test]
+    fn assembles_volumes_with_more_than_a_handful_of_segments() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        // Vary each segment's own sector count slightly so the
+        // `sectors_are_total` heuristic (all entries agreeing on
+        // `num_sectors`) doesn't kick in -- this test is about each
+        // segment reporting its own size, not the volume's total.
+        let entries: Vec<_> = (0..6).map(|i| entry(i, 6, i * 2000, 1000 + i)).collect();
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let (vol, warnings) = build_volume_lenient(&partition, &entries, "SYS", image_len, false, None).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(vol.segments().len(), 6);
+
+        let expected_total: u32 = (0..6).map(|i: u32| (1000 + i) * 512 / 4096).sum();
+        assert_eq!(vol.info.total_blocks, expected_total);
+
+        // A block in the last segment must resolve to an offset inside
+        // that segment's own region of the image, not wrap back into an
+        // earlier one.
+        let last_block = vol.info.total_blocks - 1;
+        let offset = vol.block_to_offset(last_block).unwrap();
+        let last_segment_start = partition.byte_offset() + u64::from(entries[5].start_sector) * 512;
+        assert!(offset >= last_segment_start);
+    }
+
+    /// Entries can be added to the table in any order, and the segment
+    /// with the lowest `segment_num` isn't necessarily the first one
+    /// found -- block 0 of the volume must still land at the start of
+    /// whichever segment actually claims `segment_num == 0`, regardless of
+    /// where that segment appears in `entries`.
+    #[test]
+    fn assembles_correctly_when_segment_zero_is_not_first_in_the_table() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        // segment_num 1 appears before segment_num 0 in on-disk order.
+        let entries = vec![entry(1, 2, 5000, 1001), entry(0, 2, 0, 1000)];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let (vol, _) = build_volume_lenient(&partition, &entries, "SYS", image_len, false, None).unwrap();
+        let seg0 = vol.segments().iter().find(|s| s.segment_num == 0).unwrap();
+        assert_eq!(seg0.first_block, 0);
+    }
+
+    /// With a `segment_order` override, assembly follows the given indices
+    /// into the table-order entries instead of sorting by `segment_num` --
+    /// here the entries' own `segment_num` fields would (wrongly) sort to
+    /// the same order they're already in, so swapping the override proves
+    /// the override, not the `segment_num` sort, decided the result.
+    #[test]
+    fn segment_order_override_assembles_in_the_given_index_order_instead_of_by_segment_num() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        // On-disk order: index 0 has segment_num 0 (1000 sectors), index 1
+        // has segment_num 1 (1001 sectors). Overriding to [1, 0] must put
+        // the second entry's blocks first regardless of its segment_num.
+        let entries = vec![entry(0, 2, 0, 1000), entry(1, 2, 5000, 1001)];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let (vol, warnings) = build_volume_lenient(&partition, &entries, "SYS", image_len, false, Some(&[1, 0])).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("manually overridden"));
+
+        let first_segment_blocks = (1001 * 512) / 4096;
+        assert_eq!(vol.block_to_offset(0).unwrap(), entries[1].start_sector as u64 * 512);
+        assert_eq!(
+            vol.block_to_offset(first_segment_blocks).unwrap(),
+            entries[0].start_sector as u64 * 512
+        );
+    }
+
+    /// An override whose indices don't form a full permutation of the
+    /// matched entries (wrong length, or an out-of-range index) must be
+    /// rejected rather than silently assembling a volume with duplicated or
+    /// missing segments.
+    #[test]
+    fn segment_order_override_rejects_an_index_out_of_range() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        let entries = vec![entry(0, 2, 0, 1000), entry(1, 2, 5000, 1001)];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let result = build_volume_lenient(&partition, &entries, "SYS", image_len, false, Some(&[0, 2]));
+        assert!(matches!(result, Err(NwfsError::InvalidSegmentOrder { num_segments: 2, .. })));
+    }
+
+    /// A partition that's missing one of a spanned volume's segments (e.g.
+    /// because the sibling partition holding it wasn't included in the
+    /// image) must fail loudly, naming the missing segment, instead of
+    /// silently assembling a volume with a hole in its block range.
+    #[test]
+    fn rejects_volume_with_a_missing_segment() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        // Volume claims 3 segments total, but only 0 and 2 are present.
+        let entries = vec![entry(0, 3, 0, 1000), entry(2, 3, 4000, 1000)];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let result = build_volume_lenient(&partition, &entries, "SYS", image_len, false, None);
+        match result {
+            Err(NwfsError::IncompleteVolumeSegments { missing, found, expected, .. }) => {
+                assert_eq!(missing, vec![1]);
+                assert_eq!(found, 2);
+                assert_eq!(expected, 3);
+            }
+            other => panic!("expected IncompleteVolumeSegments, got {other:?}"),
+        }
+
+        let (_, warnings) = build_volume_lenient(&partition, &entries, "SYS", image_len, true, None).unwrap();
+        assert_eq!(warnings.len(), 1);
+    }
+
+    /// Two unrelated volumes that happen to share a name (e.g. each disk in
+    /// a multi-disk server has its own "DATA" volume) must not be merged
+    /// into one just because their names match -- that would silently
+    /// interleave two unrelated segment ranges into a single corrupt
+    /// volume.
+    #[test]
+    fn rejects_segments_of_differently_numbered_volumes_sharing_a_name() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        let mut a = entry(0, 1, 0, 1000);
+        a.volume_number = 1;
+        let mut b = entry(0, 1, 4000, 1000);
+        b.volume_number = 2;
+        let entries = vec![a, b];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let result = build_volume_lenient(&partition, &entries, "SYS", image_len, false, None);
+        match result {
+            Err(NwfsError::AmbiguousVolumeName { volume_numbers, .. }) => {
+                assert_eq!(volume_numbers, vec![1, 2]);
+            }
+            other => panic!("expected AmbiguousVolumeName, got {other:?}"),
+        }
+
+        let (vol, warnings) = build_volume_lenient(&partition, &entries, "SYS", image_len, true, None).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(vol.segments().len(), 1);
+    }
+
+    /// `ById` must find the entry whose `volume_number` matches, not just
+    /// the first one in table order -- this is the whole point of
+    /// selecting by id instead of by position.
+    #[test]
+    fn select_volume_by_id_finds_the_matching_entry_regardless_of_order() {
+        let mut first = entry(0, 1, 0, 1000);
+        first.name = "SYS".to_string();
+        first.volume_number = 1;
+        let mut second = entry(0, 1, 4000, 1000);
+        second.name = "DATA".to_string();
+        second.volume_number = 2;
+        let entries = vec![first, second];
+
+        let chosen = select_volume(&entries, VolumeSelector::ById(2)).unwrap();
+        assert_eq!(chosen.name, "DATA");
+
+        let chosen = select_volume(&entries, VolumeSelector::Auto).unwrap();
+        assert_eq!(chosen.name, "SYS");
+    }
+
+    /// An id that matches no entry's `volume_number` must report which id
+    /// was asked for, not just "no volumes found" -- the table isn't
+    /// empty, the id just doesn't exist in it.
+    #[test]
+    fn select_volume_by_id_errors_on_an_unknown_id() {
+        let entries = vec![entry(0, 1, 0, 1000)];
+        let result = select_volume(&entries, VolumeSelector::ById(99));
+        assert!(matches!(result, Err(NwfsError::NoVolumeWithId { id: 99 })));
+    }
+
+    /// Two volumes sharing a name must still come back as two separate
+    /// summaries, grouped by `volume_number`, each with its own segment
+    /// merged correctly -- grouping by name alone would wrongly merge them
+    /// into one.
+    #[test]
+    fn list_volumes_groups_by_volume_number_not_by_name() {
+        let mut first_seg0 = entry(0, 2, 0, 1000);
+        first_seg0.name = "DATA".to_string();
+        first_seg0.volume_number = 1;
+        let mut first_seg1 = entry(1, 2, 1000, 1000);
+        first_seg1.name = "DATA".to_string();
+        first_seg1.volume_number = 1;
+        let mut second = entry(0, 1, 5000, 500);
+        second.name = "DATA".to_string();
+        second.volume_number = 2;
+        let entries = vec![first_seg0, first_seg1, second];
+
+        let mut volumes = list_volumes(&entries);
+        volumes.sort_by_key(|v| v.volume_number);
+
+        assert_eq!(volumes.len(), 2);
+        assert_eq!(volumes[0].volume_number, 1);
+        assert_eq!(volumes[0].num_segments, 2);
+        assert_eq!(volumes[0].total_sectors, 2000);
+        assert_eq!(volumes[1].volume_number, 2);
+        assert_eq!(volumes[1].num_segments, 1);
+        assert_eq!(volumes[1].total_sectors, 500);
+    }
+
+    /// A `block_size` of 0 is used as a divisor when turning sector counts
+    /// into block counts; a corrupt table entry reporting it must produce a
+    /// typed error instead of a division-by-zero panic.
+    #[test]
+    fn rejects_a_zero_block_size_in_strict_mode() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        let mut e = entry(0, 1, 0, 1000);
+        e.block_size = 0;
+        let entries = vec![e];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let result = build_volume_lenient(&partition, &entries, "SYS", image_len, false, None);
+        assert!(matches!(result, Err(NwfsError::ZeroBlockSize { .. })));
+    }
+
+    /// In lenient mode, a zero `block_size` on one segment falls back to
+    /// another matching segment's usable `block_size` instead of failing
+    /// outright, with a warning explaining the substitution.
+    #[test]
+    fn lenient_mode_substitutes_another_segments_block_size_for_a_zero_one() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        let mut first = entry(0, 2, 0, 1000);
+        first.block_size = 0;
+        let second = entry(1, 2, 1000, 1000);
+        let entries = vec![first, second];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let (vol, warnings) = build_volume_lenient(&partition, &entries, "SYS", image_len, true, None).unwrap();
+        assert_eq!(vol.block_size, 4096);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("block_size 0"));
+    }
+
+    /// When every matching segment reports a zero `block_size`, there's no
+    /// usable fallback left, so even lenient mode must still error.
+    #[test]
+    fn lenient_mode_still_errors_when_every_segment_has_a_zero_block_size() {
+        let partition = PartitionEntry {
+            index: 0,
+            partition_type: crate::mbr::PARTITION_TYPE_NWFS386,
+            lba_start: 0,
+            num_sectors: 1_000_000,
+        };
+        let mut e = entry(0, 1, 0, 1000);
+        e.block_size = 0;
+        let entries = vec![e];
+        let image_len = partition.byte_offset() + 1_000_000 * 512;
+
+        let result = build_volume_lenient(&partition, &entries, "SYS", image_len, true, None);
+        assert!(matches!(result, Err(NwfsError::ZeroBlockSize { .. })));
+    }
+}
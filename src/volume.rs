@@ -0,0 +1,717 @@
+//! A `LogicalVolume` is a NetWare volume as seen by the rest of the crate:
+//! a flat block address space, possibly backed by more than one on-disk
+//! segment.
+
+use std::io::SeekFrom;
+
+use crate::bytes::u32_le;
+use crate::error::{NwfsError, Result};
+use crate::hotfix::HotfixTable;
+use crate::source::Source;
+
+/// FAT chain terminator: a block whose FAT entry holds this value is the
+/// last block of its chain. Named so a reader can tell at a glance that a
+/// `0xffff_ffff` here means "end of chain", not [`crate::dirent::DIRID_AVAILABLE`]
+/// or any other sentinel that happens to share the same bit pattern.
+pub const FAT_END: u32 = 0xffff_ffff;
+
+/// Upper bound on how much we'll eagerly preallocate for a single chain
+/// read based on a declared `length`. That field comes straight off disk,
+/// so a corrupt directory entry claiming a length near `u32::MAX` must not
+/// be allowed to drive an equally large up-front allocation -- the chain
+/// walk below still reads at most `length` bytes, this only caps the
+/// capacity hint.
+const MAX_CHAIN_PREALLOC: usize = 64 * 1024 * 1024;
+
+/// One contiguous piece of a volume. Volumes that outgrew a single
+/// partition are split across several segments, each of which records
+/// which logical block range it covers and where that range lives inside
+/// the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub segment_num: u32,
+    pub block_size: u32,
+    /// First logical volume block this segment covers.
+    pub first_block: u32,
+    pub num_blocks: u32,
+    /// Byte offset within the image where this segment's block 0 starts.
+    pub image_offset: u64,
+}
+
+impl Segment {
+    pub fn last_block(&self) -> u32 {
+        self.first_block + self.num_blocks - 1
+    }
+
+    pub fn contains_block(&self, block: u32) -> bool {
+        block >= self.first_block && block <= self.last_block()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VolumeInfo {
+    pub name: String,
+    pub total_blocks: u32,
+    /// This volume's `volume_number`, copied from the volume segment table
+    /// entries that make it up. Unlike `name`, which two unrelated volumes
+    /// (e.g. on different disks) can share, `volume_number` is the field
+    /// [`crate::voltab::build_volume_lenient`] already uses to tell such
+    /// volumes apart -- kept here too so a volume can be identified by it
+    /// after assembly, not just during.
+    pub volume_number: u32,
+}
+
+/// A NetWare volume, addressed as a flat sequence of logical blocks backed
+/// by one or more [`Segment`]s.
+#[derive(Debug, Clone)]
+pub struct LogicalVolume {
+    pub info: VolumeInfo,
+    /// The volume's block size, validated once in [`LogicalVolume::build`]
+    /// against every segment's own `block_size` (agreement required, or a
+    /// per-conflict warning in lenient mode) rather than re-read from
+    /// `segments[0]` -- or anywhere else -- at each call site.
+    pub block_size: u32,
+    segments: Vec<Segment>,
+    /// Total size of the backing image in bytes, used to bounds-check every
+    /// computed offset against the actual file rather than trusting
+    /// on-disk metadata alone.
+    image_len: u64,
+    /// Bad-sector redirection table for this volume's partition, if any.
+    /// When set, every block address passed to [`LogicalVolume::block_to_offset`]
+    /// is redirected first, so a block remapped away from a detected bad
+    /// sector is read from its replacement location rather than the
+    /// original (possibly unreadable) one.
+    hotfix: Option<HotfixTable>,
+}
+
+impl LogicalVolume {
+    /// Build a volume from its segments, verifying that every segment
+    /// agrees on `block_size`. `image_len` is the size of the backing image
+    /// file and is used to reject offsets that corrupt metadata computed
+    /// past the end of it.
+    pub fn new(info: VolumeInfo, segments: Vec<Segment>, image_len: u64) -> Result<Self> {
+        let (result, warnings) = Self::build(info, segments, image_len, false);
+        debug_assert!(warnings.is_empty());
+        result
+    }
+
+    /// Like [`LogicalVolume::new`], but instead of failing on a
+    /// `block_size` mismatch between segments, falls back to the first
+    /// segment's `block_size` for the whole volume and returns a
+    /// human-readable warning per conflicting segment. Intended for
+    /// recovery tools that would rather read a volume with a guessed block
+    /// size than refuse to read it at all.
+    pub fn new_lenient(info: VolumeInfo, segments: Vec<Segment>, image_len: u64) -> Result<(Self, Vec<String>)> {
+        let (result, warnings) = Self::build(info, segments, image_len, true);
+        result.map(|vol| (vol, warnings))
+    }
+
+    fn build(info: VolumeInfo, segments: Vec<Segment>, image_len: u64, lenient: bool) -> (Result<Self>, Vec<String>) {
+        let mut warnings = Vec::new();
+        if segments.is_empty() {
+            return (Err(NwfsError::Other("volume has no segments".into())), warnings);
+        }
+        let block_size = segments[0].block_size;
+        for seg in &segments[1..] {
+            if seg.block_size != block_size {
+                if !lenient {
+                    return (
+                        Err(NwfsError::BlockSizeMismatch {
+                            a: block_size,
+                            b: seg.block_size,
+                        }),
+                        warnings,
+                    );
+                }
+                warnings.push(format!(
+                    "segment {} reports block_size={} but volume uses {}; treating it as {}",
+                    seg.segment_num, seg.block_size, block_size, block_size
+                ));
+            }
+        }
+
+        let computed_blocks: u32 = segments.iter().map(|s| s.num_blocks).sum();
+        if computed_blocks != info.total_blocks {
+            if !lenient {
+                return (
+                    Err(NwfsError::VolumeBlockCountMismatch {
+                        name: info.name.clone(),
+                        expected: info.total_blocks,
+                        computed: computed_blocks,
+                    }),
+                    warnings,
+                );
+            }
+            warnings.push(format!(
+                "volume '{}' reports total_blocks={} but its segments cover {} block(s); a disk of a spanned volume may be missing",
+                info.name, info.total_blocks, computed_blocks
+            ));
+        }
+
+        (
+            Ok(Self {
+                info,
+                block_size,
+                segments,
+                image_len,
+                hotfix: None,
+            }),
+            warnings,
+        )
+    }
+
+    /// Attach a bad-sector redirection table, so every subsequent block
+    /// read transparently follows its remapping instead of the caller
+    /// having to consult the table itself.
+    pub fn with_hotfix(mut self, hotfix: HotfixTable) -> Self {
+        self.hotfix = Some(hotfix);
+        self
+    }
+
+    /// Force the block size used for every subsequent block-address
+    /// calculation, bypassing whatever this volume's segments reported.
+    /// For recovery when a volume header's `block_size` field is corrupt
+    /// but the underlying data and segment layout are intact: each
+    /// segment's `image_offset` is computed from its on-disk sector
+    /// offset, not from `block_size`, so it stays valid under an
+    /// override -- only the stride used to walk blocks within a segment
+    /// changes. A wrong value here yields garbage reads, not an error;
+    /// there's no way to tell a "wrong" override from a "right" one just
+    /// by looking at the bytes it produces.
+    pub fn with_block_size(mut self, block_size: u32) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// The segment backing `block`, e.g. to report which physical disk of
+    /// a spanned volume a given block lives on.
+    pub fn segment_for_block(&self, block: u32) -> Result<&Segment> {
+        self.segments
+            .iter()
+            .find(|s| s.contains_block(block))
+            .ok_or(NwfsError::BlockOutOfRange {
+                offset: u64::from(block) * u64::from(self.block_size),
+                len: self.block_size as usize,
+            })
+    }
+
+    /// Translate a logical block number into a byte offset within the
+    /// image that backs it. `block` is redirected through the volume's
+    /// hotfix table first, if one is attached, so a block remapped away
+    /// from a detected bad sector resolves to its replacement location.
+    /// The offset is checked against the image's actual length, not just
+    /// the segment's declared range, so a corrupt segment table can't send
+    /// a read into a neighboring partition or past end-of-file.
+    pub fn block_to_offset(&self, block: u32) -> Result<u64> {
+        let block = self.hotfix.as_ref().map_or(block, |h| h.redirect(block));
+        let seg = self.segment_for_block(block)?;
+        let blocks_into_segment = u64::from(block - seg.first_block);
+        let offset = seg.image_offset + blocks_into_segment * u64::from(self.block_size);
+        if offset + u64::from(self.block_size) > self.image_len {
+            return Err(NwfsError::BlockOutOfRange {
+                offset,
+                len: self.block_size as usize,
+            });
+        }
+        Ok(offset)
+    }
+
+    pub fn seek_block(&self, file: &mut dyn Source, block: u32) -> Result<()> {
+        let offset = self.block_to_offset(block)?;
+        file.seek(SeekFrom::Start(offset)).map_err(|source| NwfsError::BlockIo {
+            operation: "seek",
+            block,
+            offset,
+            source,
+        })?;
+        Ok(())
+    }
+
+    pub fn read_block(&self, file: &mut dyn Source, block: u32, buf: &mut [u8]) -> Result<()> {
+        if buf.len() > self.block_size as usize {
+            let offset = self.block_to_offset(block)?;
+            return Err(NwfsError::BlockOutOfRange { offset, len: buf.len() });
+        }
+        self.seek_block(file, block)?;
+        let offset = self.block_to_offset(block)?;
+        file.read_exact(buf).map_err(|source| NwfsError::BlockIo {
+            operation: "read",
+            block,
+            offset,
+            source,
+        })
+    }
+
+    /// Read FAT entry `index` out of a `total_entries`-entry table starting
+    /// at logical block `fat_first_block`, rejecting `index` values past
+    /// the end of the table instead of silently reading whatever follows
+    /// the FAT on disk.
+    pub fn read_fat_entry(&self, file: &mut dyn Source, fat_first_block: u32, total_entries: u32, index: u32) -> Result<u32> {
+        if index >= total_entries {
+            return Err(NwfsError::FatCorrupt {
+                offset: u64::from(index) * 4,
+            });
+        }
+        let entries_per_block = self.block_size / 4;
+        let block = fat_first_block + index / entries_per_block;
+        let offset_in_block = ((index % entries_per_block) * 4) as usize;
+
+        let mut buf = vec![0u8; self.block_size as usize];
+        self.read_block(file, block, &mut buf)?;
+        Ok(u32_le(&buf, offset_in_block))
+    }
+
+    /// Logical block at which the volume's FAT begins. Block 0 is reserved.
+    pub fn fat_first_block(&self) -> u32 {
+        1
+    }
+
+    pub fn fat_num_blocks(&self) -> u32 {
+        let fat_bytes = u64::from(self.info.total_blocks) * 4;
+        fat_bytes.div_ceil(u64::from(self.block_size)) as u32
+    }
+
+    /// Logical block at which the root directory table begins, directly
+    /// after the FAT.
+    pub fn dir_first_block(&self) -> u32 {
+        self.fat_first_block() + self.fat_num_blocks()
+    }
+
+    /// Logical block at which the mirrored second copy of the root
+    /// directory table begins, directly after the primary copy. NetWare
+    /// keeps two copies of the directory table for recovery when one is
+    /// damaged; `num_dir_blocks` is the size of a single copy, in blocks
+    /// (the primary copy's size, e.g. [`crate::voltab::INITIAL_DIR_BLOCKS`]).
+    pub fn dir_first_block_copy2(&self, num_dir_blocks: u32) -> u32 {
+        self.dir_first_block() + num_dir_blocks
+    }
+
+    /// Follow a FAT chain starting at `first_block`, reading at most
+    /// `length` bytes of data. `fat` is indexed by block number, with each
+    /// entry giving the next block in the chain or [`FAT_END`] at EOF.
+    ///
+    /// `first_block == FAT_END` is a sentinel some directory entries use
+    /// for "no data", rather than a real block address -- it's returned as
+    /// an empty chain here instead of being looked up and failing with
+    /// [`NwfsError::BlockOutOfRange`]. `first_block == 0` is not special:
+    /// block 0 is ordinarily a segment's first real block, so it's read
+    /// like any other address.
+    pub fn read_chain(&self, file: &mut dyn Source, fat: &[u32], first_block: u32, length: u32) -> Result<Vec<u8>> {
+        if first_block == FAT_END {
+            return Ok(Vec::new());
+        }
+        let mut data = Vec::with_capacity((length as usize).min(MAX_CHAIN_PREALLOC));
+        let mut block = first_block;
+        let mut block_buf = vec![0u8; self.block_size as usize];
+        while data.len() < length as usize {
+            self.read_block(file, block, &mut block_buf)?;
+            let remaining = length as usize - data.len();
+            data.extend_from_slice(&block_buf[..remaining.min(block_buf.len())]);
+
+            let next = *fat.get(block as usize).ok_or(NwfsError::FatCorrupt {
+                offset: u64::from(block) * 4,
+            })?;
+            if next == FAT_END || next == block {
+                break;
+            }
+            block = next;
+        }
+        Ok(data)
+    }
+
+    /// Like [`LogicalVolume::read_chain`], but the final block is read in
+    /// full rather than truncated to `length`, and the trailing bytes
+    /// beyond `length` -- the block's slack space, potentially holding
+    /// leftover data from whatever file occupied that block before -- are
+    /// returned alongside the normal data instead of being discarded.
+    /// Forensic recovery is the only reason to want this; every other
+    /// caller wants [`LogicalVolume::read_chain`] instead.
+    ///
+    /// Treats `first_block == FAT_END` as "no data" the same way
+    /// [`LogicalVolume::read_chain`] does.
+    pub fn read_chain_with_slack(
+        &self,
+        file: &mut dyn Source,
+        fat: &[u32],
+        first_block: u32,
+        length: u32,
+    ) -> Result<(Vec<u8>, usize)> {
+        if first_block == FAT_END {
+            return Ok((Vec::new(), 0));
+        }
+        let mut data = Vec::with_capacity((length as usize).min(MAX_CHAIN_PREALLOC));
+        let mut block = first_block;
+        let mut block_buf = vec![0u8; self.block_size as usize];
+        let mut slack = 0usize;
+        while data.len() < length as usize {
+            self.read_block(file, block, &mut block_buf)?;
+            let remaining = length as usize - data.len();
+            if remaining >= block_buf.len() {
+                data.extend_from_slice(&block_buf);
+            } else {
+                data.extend_from_slice(&block_buf[..remaining]);
+                slack = block_buf.len() - remaining;
+                data.extend_from_slice(&block_buf[remaining..]);
+            }
+
+            let next = *fat.get(block as usize).ok_or(NwfsError::FatCorrupt {
+                offset: u64::from(block) * 4,
+            })?;
+            if next == FAT_END || next == block {
+                break;
+            }
+            block = next;
+        }
+        Ok((data, slack))
+    }
+
+    /// Like [`LogicalVolume::read_chain`], but streams each block straight
+    /// to `out` instead of accumulating the whole file in a `Vec` first --
+    /// the non-allocating counterpart for piping a large file to stdout, a
+    /// hasher, or a `BufWriter<File>` without holding its entire contents
+    /// in memory at once. Returns the number of bytes written.
+    ///
+    /// Treats `first_block == FAT_END` as "no data" the same way
+    /// [`LogicalVolume::read_chain`] does. The chain is bounded by `length`
+    /// the same way too: each block advances toward `length` bytes
+    /// written, so a cycle in a corrupt FAT repeats its blocks only until
+    /// `length` bytes have been written -- it cannot spin forever.
+    pub fn extract_to(
+        &self,
+        file: &mut dyn Source,
+        fat: &[u32],
+        first_block: u32,
+        length: u32,
+        out: &mut dyn std::io::Write,
+    ) -> Result<u64> {
+        if first_block == FAT_END {
+            return Ok(0);
+        }
+        let mut written = 0u64;
+        let mut block = first_block;
+        let mut block_buf = vec![0u8; self.block_size as usize];
+        while written < u64::from(length) {
+            self.read_block(file, block, &mut block_buf)?;
+            let remaining = u64::from(length) - written;
+            let take = remaining.min(block_buf.len() as u64) as usize;
+            out.write_all(&block_buf[..take]).map_err(|source| NwfsError::BlockIo {
+                operation: "extract_to write",
+                block,
+                offset: self.block_to_offset(block).unwrap_or(0),
+                source,
+            })?;
+            written += take as u64;
+
+            let next = *fat.get(block as usize).ok_or(NwfsError::FatCorrupt {
+                offset: u64::from(block) * 4,
+            })?;
+            if next == FAT_END || next == block {
+                break;
+            }
+            block = next;
+        }
+        Ok(written)
+    }
+}
+
+/// Render `bytes` in whichever of B/KB/MB/GB/TB keeps the mantissa
+/// readable: plain bytes below 1 KB, two decimal places for every larger
+/// unit (so exactly 1 GiB is "1.00 GB").
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{value:.2} {}", UNITS[unit])
+    }
+}
+
+impl std::fmt::Display for LogicalVolume {
+    /// A human-readable one-liner: name, block size, total size, the
+    /// segment(s) making it up, and the root directory's first block.
+    /// [`VolumeInfo`] alone only carries `name`, `total_blocks`, and
+    /// `volume_number` -- block size and segment layout live on
+    /// `LogicalVolume` -- so the full summary is implemented here rather
+    /// than as `VolumeInfo`'s own `Display`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total_bytes = u64::from(self.block_size) * u64::from(self.info.total_blocks);
+        let segment_nums: Vec<String> = self.segments.iter().map(|s| s.segment_num.to_string()).collect();
+        write!(
+            f,
+            "'{}': {} KB blocks, {} total, segment(s) [{}], root dir block {}",
+            self.info.name,
+            self.block_size / 1024,
+            human_bytes(total_bytes),
+            segment_nums.join(", "),
+            self.dir_first_block()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotfix::HotfixEntry;
+    use std::fs::File;
+    use std::io::Write;
+
+    /// A volume with remapping active must read a redirected block's data
+    /// from its *replacement* location, not the original (bad) one --
+    /// otherwise callers silently get garbage or stale bytes back.
+    #[test]
+    fn block_to_offset_follows_hotfix_redirection() {
+        let block_size = 512u32;
+        let num_blocks = 4u32;
+        let mut image_bytes = vec![0u8; (block_size * num_blocks) as usize];
+        // Block 2's replacement (block 3) is marked distinctly so the test
+        // can tell which one was actually read.
+        image_bytes[(3 * block_size) as usize] = 0xaa;
+
+        let path = std::env::temp_dir().join(format!("nwfs_hotfix_test_{}.img", std::process::id()));
+        {
+            let mut f = File::create(&path).unwrap();
+            f.write_all(&image_bytes).unwrap();
+        }
+
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: num_blocks,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size,
+            first_block: 0,
+            num_blocks,
+            image_offset: 0,
+        }];
+        let image_len = image_bytes.len() as u64;
+        let vol = LogicalVolume::new(info, segments, image_len)
+            .unwrap()
+            .with_hotfix(HotfixTable::from_entries(vec![HotfixEntry {
+                original_block: 2,
+                redirect_block: 3,
+            }]));
+
+        let mut file = File::open(&path).unwrap();
+        let mut buf = vec![0u8; block_size as usize];
+        vol.read_block(&mut file, 2, &mut buf).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(buf[0], 0xaa);
+        assert_eq!(vol.block_to_offset(2).unwrap(), u64::from(3 * block_size));
+    }
+
+    /// A directory entry with no data uses `first_block == FAT_END` as a
+    /// sentinel rather than a real address; `read_chain` must recognize it
+    /// and return an empty chain instead of trying to read block
+    /// `0xffff_ffff` and failing with `BlockOutOfRange`.
+    #[test]
+    fn read_chain_treats_fat_end_first_block_as_empty() {
+        let block_size = 512u32;
+        let num_blocks = 4u32;
+        let image_bytes = vec![0u8; (block_size * num_blocks) as usize];
+
+        let path = std::env::temp_dir().join(format!("nwfs_fatend_test_{}.img", std::process::id()));
+        {
+            let mut f = File::create(&path).unwrap();
+            f.write_all(&image_bytes).unwrap();
+        }
+
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: num_blocks,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size,
+            first_block: 0,
+            num_blocks,
+            image_offset: 0,
+        }];
+        let image_len = image_bytes.len() as u64;
+        let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+        let fat = vec![FAT_END; num_blocks as usize];
+
+        let mut file = File::open(&path).unwrap();
+        let data = vol.read_chain(&mut file, &fat, FAT_END, 11).unwrap();
+        let (data_with_slack, slack) = vol.read_chain_with_slack(&mut file, &fat, FAT_END, 11).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(data.is_empty());
+        assert!(data_with_slack.is_empty());
+        assert_eq!(slack, 0);
+    }
+
+    /// A directory entry claiming a `length` near `u32::MAX` -- plausible
+    /// for a corrupt entry, never for real data -- must not make
+    /// `read_chain` try to preallocate that much memory up front. The
+    /// chain itself still ends wherever the FAT says it does, so only the
+    /// capacity hint is affected, not the returned data.
+    #[test]
+    fn read_chain_caps_preallocation_for_an_implausibly_large_declared_length() {
+        let block_size = 512u32;
+        let num_blocks = 2u32;
+        let image_bytes = vec![0xAAu8; (block_size * num_blocks) as usize];
+
+        let path = std::env::temp_dir().join(format!("nwfs_hugelen_test_{}.img", std::process::id()));
+        {
+            let mut f = File::create(&path).unwrap();
+            f.write_all(&image_bytes).unwrap();
+        }
+
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: num_blocks,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size,
+            first_block: 0,
+            num_blocks,
+            image_offset: 0,
+        }];
+        let image_len = image_bytes.len() as u64;
+        let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+        let fat = vec![FAT_END, FAT_END];
+
+        let mut file = File::open(&path).unwrap();
+        let data = vol.read_chain(&mut file, &fat, 0, u32::MAX).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(data.len(), block_size as usize);
+        assert!(data.capacity() <= MAX_CHAIN_PREALLOC);
+    }
+
+    /// `extract_to` must stream the same bytes `read_chain` returns,
+    /// including across a multi-block chain, and report the total written
+    /// back to the caller.
+    #[test]
+    fn extract_to_streams_the_same_bytes_as_read_chain() {
+        let block_size = 512u32;
+        let num_blocks = 3u32;
+        let mut image_bytes = vec![0u8; (block_size * num_blocks) as usize];
+        image_bytes[0] = 0xaa;
+        image_bytes[block_size as usize] = 0xbb;
+
+        let path = std::env::temp_dir().join(format!("nwfs_extract_to_test_{}.img", std::process::id()));
+        {
+            let mut f = File::create(&path).unwrap();
+            f.write_all(&image_bytes).unwrap();
+        }
+
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: num_blocks,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size,
+            first_block: 0,
+            num_blocks,
+            image_offset: 0,
+        }];
+        let image_len = image_bytes.len() as u64;
+        let vol = LogicalVolume::new(info, segments, image_len).unwrap();
+        let fat = vec![1, FAT_END, FAT_END];
+        let length = block_size + 5;
+
+        let mut file = File::open(&path).unwrap();
+        let expected = vol.read_chain(&mut file, &fat, 0, length).unwrap();
+        let mut out = Vec::new();
+        let written = vol.extract_to(&mut file, &fat, 0, length, &mut out).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(written, u64::from(length));
+        assert_eq!(out, expected);
+    }
+
+    /// `total_blocks` disagreeing with the segments' combined `num_blocks`
+    /// means a disk of a spanned volume is missing or the segment table is
+    /// corrupt -- `new` must refuse rather than silently build a volume
+    /// that doesn't cover what it claims to.
+    #[test]
+    fn new_rejects_a_total_blocks_mismatch() {
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: 100,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size: 512,
+            first_block: 0,
+            num_blocks: 4,
+            image_offset: 0,
+        }];
+        let err = LogicalVolume::new(info, segments, 4 * 512).unwrap_err();
+        assert!(matches!(
+            err,
+            NwfsError::VolumeBlockCountMismatch {
+                expected: 100,
+                computed: 4,
+                ..
+            }
+        ));
+    }
+
+    /// `new_lenient` must warn on the same mismatch instead of refusing to
+    /// build the volume, matching every other lenient-mode check.
+    #[test]
+    fn new_lenient_warns_on_a_total_blocks_mismatch() {
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: 100,
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size: 512,
+            first_block: 0,
+            num_blocks: 4,
+            image_offset: 0,
+        }];
+        let (_vol, warnings) = LogicalVolume::new_lenient(info, segments, 4 * 512).unwrap();
+        assert!(warnings.iter().any(|w| w.contains("total_blocks=100")));
+    }
+
+    /// The `Display` summary must report block size and total size in
+    /// human units (not a raw block count) and name the root directory
+    /// block, so a reader doesn't have to do the block_size arithmetic
+    /// themselves.
+    #[test]
+    fn display_reports_human_readable_size_and_root_dir_block() {
+        let info = VolumeInfo {
+            name: "SYS".to_string(),
+            total_blocks: 262144, // 4 KB blocks * 262144 = 1 GB
+            volume_number: 0,
+        };
+        let segments = vec![Segment {
+            segment_num: 0,
+            block_size: 4096,
+            first_block: 0,
+            num_blocks: 262144,
+            image_offset: 0,
+        }];
+        let vol = LogicalVolume::new(info, segments, 4096 * 262144).unwrap();
+
+        let summary = vol.to_string();
+        assert!(summary.contains("'SYS'"));
+        assert!(summary.contains("4 KB blocks"));
+        assert!(summary.contains("1.00 GB"));
+        assert!(summary.contains(&format!("root dir block {}", vol.dir_first_block())));
+    }
+}